@@ -0,0 +1,179 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::hint::black_box;
+
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Section<'a> {
+    name: &'a str,
+    enabled: bool,
+    port: u16,
+    ratio: f64,
+    tags: Vec<&'a str>,
+    limits: BTreeMap<&'a str, &'a str>,
+}
+
+#[must_use]
+fn generate_flat(count: usize) -> String {
+    let mut document = String::new();
+
+    for i in 0..count {
+        writeln!(
+            document,
+            "key_{i} = value number {i} with some padding text"
+        )
+        .unwrap();
+    }
+
+    document
+}
+
+#[must_use]
+fn generate_nested(count: usize) -> String {
+    let mut document = String::new();
+
+    for i in 0..count {
+        writeln!(document, "section_{i} =").unwrap();
+        writeln!(document, "    name = Section {i}").unwrap();
+        writeln!(document, "    enabled = true").unwrap();
+        writeln!(document, "    port = {}", 8000 + i % 1000).unwrap();
+        writeln!(document, "    ratio = 0.{i}").unwrap();
+        writeln!(document, "    tags =").unwrap();
+        writeln!(document, "        = alpha").unwrap();
+        writeln!(document, "        = beta").unwrap();
+        writeln!(document, "        = gamma").unwrap();
+        writeln!(document, "    limits =").unwrap();
+        writeln!(document, "        cpu = 1500mi").unwrap();
+        writeln!(document, "        memory = 10Gb").unwrap();
+        writeln!(document).unwrap();
+    }
+
+    document
+}
+
+#[must_use]
+fn generate_array(count: usize) -> String {
+    let mut document = String::from("values =\n");
+
+    for i in 0..count {
+        writeln!(document, "    = {i}").unwrap();
+    }
+
+    document
+}
+
+#[must_use]
+fn generate_multiline(count: usize) -> String {
+    let mut document = String::new();
+
+    for i in 0..count {
+        writeln!(document, "description_{i} =").unwrap();
+
+        for line in 0..8 {
+            writeln!(
+                document,
+                "    line {line} of a long free-form description without any equal signs",
+            )
+            .unwrap();
+        }
+    }
+
+    document
+}
+
+fn bench_flat(c: &mut Criterion) {
+    let mut group = c.benchmark_group("flat");
+
+    for size in SIZES {
+        let document = generate_flat(size);
+        group.throughput(Throughput::Bytes(document.len() as u64));
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            &document,
+            |b, document| {
+                b.iter(|| {
+                    serde_ccl::from_str::<BTreeMap<&str, &str>>(black_box(document)).unwrap()
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_nested(c: &mut Criterion) {
+    let mut group = c.benchmark_group("nested");
+
+    for size in SIZES {
+        let document = generate_nested(size);
+        group.throughput(Throughput::Bytes(document.len() as u64));
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            &document,
+            |b, document| {
+                b.iter(|| {
+                    serde_ccl::from_str::<BTreeMap<&str, Section>>(black_box(document)).unwrap()
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_array(c: &mut Criterion) {
+    let mut group = c.benchmark_group("array");
+
+    for size in SIZES {
+        let document = generate_array(size);
+        group.throughput(Throughput::Bytes(document.len() as u64));
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            &document,
+            |b, document| {
+                b.iter(|| {
+                    serde_ccl::from_str::<BTreeMap<&str, Vec<u64>>>(black_box(document)).unwrap()
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_multiline(c: &mut Criterion) {
+    let mut group = c.benchmark_group("multiline");
+
+    for size in SIZES {
+        let document = generate_multiline(size);
+        group.throughput(Throughput::Bytes(document.len() as u64));
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            &document,
+            |b, document| {
+                b.iter(|| {
+                    serde_ccl::from_str::<BTreeMap<&str, &str>>(black_box(document)).unwrap()
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_flat,
+    bench_nested,
+    bench_array,
+    bench_multiline
+);
+criterion_main!(benches);
@@ -0,0 +1,61 @@
+use crate::server::index;
+use lsp_types::{GotoDefinitionResponse, Location, Position, Range, Url};
+use serde_ccl::{Document, FileResolver, LineIndex, Resolver, Span, Value, DEFAULT_INCLUDE_KEY};
+
+/// Returns the documents included by the path under the cursor, if the cursor
+/// is on the value of an include entry.
+pub fn definition(text: &str, uri: &Url, position: Position) -> Option<GotoDefinitionResponse> {
+    let document = Document::parse(text).ok()?;
+    let index = index(&LineIndex::new(text), position)?;
+
+    let (path, _) = include_paths(&document)
+        .into_iter()
+        .find(|(_, span)| span.start() <= index && index <= span.end())?;
+
+    let file_path = uri.to_file_path().ok()?;
+    let parent = file_path.to_str()?;
+    let resolver = FileResolver::new();
+
+    // Glob patterns contain one of these characters, like in the loader.
+    let ids = if path.contains(['*', '?', '[']) {
+        resolver.expand(path, Some(parent)).ok()?
+    } else {
+        vec![resolver.resolve(path, Some(parent)).ok()?]
+    };
+
+    let locations = ids
+        .into_iter()
+        .filter_map(|id| Url::from_file_path(id).ok())
+        .map(|uri| Location::new(uri, Range::default()))
+        .collect();
+
+    Some(GotoDefinitionResponse::Array(locations))
+}
+
+/// Returns the paths listed by the include entries of the document, at any
+/// depth, together with their spans.
+fn include_paths(block: &Document) -> Vec<(&str, Span)> {
+    let mut paths = Vec::new();
+
+    for entry in block {
+        match entry.value() {
+            Value::Text(path) if entry.key() == DEFAULT_INCLUDE_KEY => {
+                paths.push((path.as_str(), entry.origin().value_span()));
+            }
+            Value::Block(items) if entry.key() == DEFAULT_INCLUDE_KEY => {
+                paths.extend(
+                    items
+                        .iter()
+                        .filter(|item| item.key().is_empty())
+                        .filter_map(|item| {
+                            Some((item.value().as_text()?, item.origin().value_span()))
+                        }),
+                );
+            }
+            Value::Block(block) => paths.extend(include_paths(block)),
+            Value::Text(_) => (),
+        }
+    }
+
+    paths
+}
@@ -0,0 +1,47 @@
+use crate::server::{range, to_u32};
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+use serde_ccl::{ColumnMode, LineIndex};
+
+/// Name reported as the source of the diagnostics.
+const SOURCE: &str = "ccl";
+
+/// Returns the syntax error of the document, or the problems found by the
+/// linter if it parses.
+pub fn diagnostics(text: &str) -> Vec<Diagnostic> {
+    let diagnostics = match serde_ccl::lint(text) {
+        Ok(diagnostics) => diagnostics,
+        Err(error) => {
+            let position = error.position(ColumnMode::Utf16);
+            let position = Position::new(
+                to_u32(position.line.saturating_sub(1)),
+                to_u32(position.column.saturating_sub(1)),
+            );
+
+            return vec![Diagnostic {
+                range: Range::new(position, position),
+                severity: Some(DiagnosticSeverity::ERROR),
+                source: Some(SOURCE.into()),
+                message: error.message(),
+                ..Diagnostic::default()
+            }];
+        }
+    };
+
+    let line_index = LineIndex::new(text);
+
+    diagnostics
+        .into_iter()
+        .map(|diagnostic| Diagnostic {
+            range: range(
+                &line_index,
+                diagnostic.span().start(),
+                diagnostic.span().end(),
+            ),
+            severity: Some(DiagnosticSeverity::WARNING),
+            code: Some(NumberOrString::String(diagnostic.rule().name().into())),
+            source: Some(SOURCE.into()),
+            message: diagnostic.message().into(),
+            ..Diagnostic::default()
+        })
+        .collect()
+}
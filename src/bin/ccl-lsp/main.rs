@@ -0,0 +1,24 @@
+//! Language server for CCL documents, communicating over standard input and
+//! output.
+
+mod definition;
+mod diagnostics;
+mod server;
+mod symbols;
+
+use lsp_server::Connection;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let (connection, io_threads) = Connection::stdio();
+    let result = server::run(&connection);
+    drop(connection);
+
+    match result.and_then(|()| Ok(io_threads.join()?)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use crate::{definition, diagnostics, symbols};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+    PublishDiagnostics,
+};
+use lsp_types::request::{
+    DocumentSymbolRequest, FoldingRangeRequest, Formatting, GotoDefinition, Request as _,
+};
+use lsp_types::{
+    FoldingRangeProviderCapability, OneOf, Position, PublishDiagnosticsParams, Range,
+    ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit, Url,
+};
+use serde_ccl::{ColumnMode, LineIndex};
+use std::collections::HashMap;
+use std::error::Error;
+
+pub type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
+
+/// Runs the server until the client asks it to exit.
+pub fn run(connection: &Connection) -> Result<()> {
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        ..ServerCapabilities::default()
+    };
+
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+
+    let mut server = Server {
+        connection,
+        documents: HashMap::new(),
+    };
+
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+
+                server.handle_request(request)?;
+            }
+            Message::Notification(notification) => server.handle_notification(notification)?,
+            Message::Response(_) => (),
+        }
+    }
+
+    Ok(())
+}
+
+/// Open documents, by URI.
+struct Server<'a> {
+    connection: &'a Connection,
+    documents: HashMap<Url, String>,
+}
+
+impl Server<'_> {
+    fn handle_request(&self, request: Request) -> Result<()> {
+        let response = match request.method.as_str() {
+            DocumentSymbolRequest::METHOD => self
+                .respond::<DocumentSymbolRequest>(request, |text, _| {
+                    symbols::document_symbols(text?)
+                }),
+            FoldingRangeRequest::METHOD => self
+                .respond::<FoldingRangeRequest>(request, |text, _| symbols::folding_ranges(text?)),
+            Formatting::METHOD => self.respond::<Formatting>(request, |text, _| format(text?)),
+            GotoDefinition::METHOD => self.respond::<GotoDefinition>(request, |text, params| {
+                let params = params.text_document_position_params;
+                definition::definition(text?, &params.text_document.uri, params.position)
+            }),
+            _ => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unsupported method {}", request.method),
+            ),
+        };
+
+        self.connection.sender.send(Message::Response(response))?;
+        Ok(())
+    }
+
+    /// Handles a request about a document with the given handler, which
+    /// receives the text of the document if it is open.
+    fn respond<R>(
+        &self,
+        request: Request,
+        handler: impl FnOnce(Option<&str>, R::Params) -> R::Result,
+    ) -> Response
+    where
+        R: lsp_types::request::Request,
+        R::Params: HasUri,
+    {
+        let id: RequestId = request.id.clone();
+
+        match request.extract::<R::Params>(R::METHOD) {
+            Ok((id, params)) => {
+                let text = self.documents.get(params.uri()).map(String::as_str);
+                Response::new_ok(id, handler(text, params))
+            }
+            Err(error) => Response::new_err(id, ErrorCode::InvalidParams as i32, error.to_string()),
+        }
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> Result<()> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params = extract::<DidOpenTextDocument>(notification)?;
+                let document = params.text_document;
+                self.documents.insert(document.uri.clone(), document.text);
+                self.publish_diagnostics(document.uri)
+            }
+            DidChangeTextDocument::METHOD => {
+                let params = extract::<DidChangeTextDocument>(notification)?;
+
+                // Documents are synchronized in full, so the last change holds
+                // the whole text.
+                if let Some(change) = params.content_changes.into_iter().last() {
+                    let uri = params.text_document.uri;
+                    self.documents.insert(uri.clone(), change.text);
+                    self.publish_diagnostics(uri)?;
+                }
+
+                Ok(())
+            }
+            DidCloseTextDocument::METHOD => {
+                let params = extract::<DidCloseTextDocument>(notification)?;
+                self.documents.remove(&params.text_document.uri);
+                self.publish_diagnostics(params.text_document.uri)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Publishes the diagnostics of the document, or clears them if it is
+    /// closed.
+    fn publish_diagnostics(&self, uri: Url) -> Result<()> {
+        let diagnostics = self
+            .documents
+            .get(&uri)
+            .map(|text| diagnostics::diagnostics(text))
+            .unwrap_or_default();
+
+        let params = PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version: None,
+        };
+
+        let notification = Notification::new(PublishDiagnostics::METHOD.into(), params);
+        self.connection
+            .sender
+            .send(Message::Notification(notification))?;
+        Ok(())
+    }
+}
+
+fn extract<N>(notification: Notification) -> Result<N::Params>
+where
+    N: lsp_types::notification::Notification,
+{
+    Ok(notification.extract(N::METHOD)?)
+}
+
+/// Parameters of requests about a document.
+trait HasUri {
+    fn uri(&self) -> &Url;
+}
+
+impl HasUri for lsp_types::DocumentSymbolParams {
+    fn uri(&self) -> &Url {
+        &self.text_document.uri
+    }
+}
+
+impl HasUri for lsp_types::FoldingRangeParams {
+    fn uri(&self) -> &Url {
+        &self.text_document.uri
+    }
+}
+
+impl HasUri for lsp_types::DocumentFormattingParams {
+    fn uri(&self) -> &Url {
+        &self.text_document.uri
+    }
+}
+
+impl HasUri for lsp_types::GotoDefinitionParams {
+    fn uri(&self) -> &Url {
+        &self.text_document_position_params.text_document.uri
+    }
+}
+
+/// Formats the document, replacing its whole text. Documents that do not
+/// parse are left as they are.
+fn format(text: &str) -> Option<Vec<TextEdit>> {
+    let formatted = serde_ccl::format(text).ok()?;
+
+    if formatted == text {
+        return Some(Vec::new());
+    }
+
+    let line_index = LineIndex::new(text);
+
+    Some(vec![TextEdit {
+        range: range(&line_index, 0, text.len()),
+        new_text: formatted,
+    }])
+}
+
+/// Returns the LSP position of the byte at the given offset.
+pub fn position(line_index: &LineIndex, index: usize) -> Position {
+    let position = line_index.position(index, ColumnMode::Utf16);
+    Position::new(to_u32(position.line - 1), to_u32(position.column - 1))
+}
+
+/// Returns the LSP range between the given byte offsets.
+pub fn range(line_index: &LineIndex, start: usize, end: usize) -> Range {
+    Range::new(position(line_index, start), position(line_index, end))
+}
+
+/// Returns the byte offset of the LSP position, if it is inside the document.
+pub fn index(line_index: &LineIndex, position: Position) -> Option<usize> {
+    let position =
+        serde_ccl::Position::new(position.line as usize + 1, position.character as usize + 1);
+
+    line_index.index(position, ColumnMode::Utf16)
+}
+
+pub fn to_u32(value: usize) -> u32 {
+    u32::try_from(value).unwrap_or(u32::MAX)
+}
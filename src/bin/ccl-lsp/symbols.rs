@@ -0,0 +1,86 @@
+use crate::server::{range, to_u32};
+use lsp_types::{DocumentSymbol, DocumentSymbolResponse, FoldingRange, SymbolKind};
+use serde_ccl::{Document, LineIndex, Value};
+
+/// Returns the key hierarchy of the document. Comments are left out and list
+/// items are named after their index.
+pub fn document_symbols(text: &str) -> Option<DocumentSymbolResponse> {
+    let document = Document::parse(text).ok()?;
+    let line_index = LineIndex::new(text);
+
+    Some(DocumentSymbolResponse::Nested(block_symbols(
+        &document,
+        &line_index,
+    )))
+}
+
+fn block_symbols(block: &Document, line_index: &LineIndex) -> Vec<DocumentSymbol> {
+    let mut symbols = Vec::new();
+    let mut items = 0;
+
+    for entry in block.iter().filter(|entry| entry.key() != "/") {
+        let name = if entry.key().is_empty() {
+            items += 1;
+            format!("[{}]", items - 1)
+        } else {
+            entry.key().into()
+        };
+
+        let (kind, detail, children) = match entry.value() {
+            Value::Block(block) if block.is_list() => {
+                (SymbolKind::ARRAY, None, block_symbols(block, line_index))
+            }
+            Value::Block(block) => (SymbolKind::OBJECT, None, block_symbols(block, line_index)),
+            Value::Text(text) => (
+                SymbolKind::STRING,
+                text.lines().next().map(String::from),
+                Vec::new(),
+            ),
+        };
+
+        let key_span = entry.origin().key_span();
+
+        #[allow(deprecated)]
+        symbols.push(DocumentSymbol {
+            name,
+            detail,
+            kind,
+            tags: None,
+            deprecated: None,
+            range: range(line_index, key_span.start(), entry.origin().end()),
+            selection_range: range(line_index, key_span.start(), key_span.end()),
+            children: Some(children),
+        });
+    }
+
+    symbols
+}
+
+/// Returns the ranges of the entries that span multiple lines.
+pub fn folding_ranges(text: &str) -> Option<Vec<FoldingRange>> {
+    let document = Document::parse(text).ok()?;
+    let line_index = LineIndex::new(text);
+
+    let mut ranges = Vec::new();
+    push_folding_ranges(&mut ranges, &document, &line_index);
+    Some(ranges)
+}
+
+fn push_folding_ranges(ranges: &mut Vec<FoldingRange>, block: &Document, line_index: &LineIndex) {
+    for entry in block {
+        let start_line = entry.origin().key_span().line();
+        let end_line = line_index.line(entry.origin().end());
+
+        if end_line > start_line {
+            ranges.push(FoldingRange {
+                start_line: to_u32(start_line - 1),
+                end_line: to_u32(end_line - 1),
+                ..FoldingRange::default()
+            });
+        }
+
+        if let Value::Block(block) = entry.value() {
+            push_folding_ranges(ranges, block, line_index);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use clap::builder::PossibleValuesParser;
+use clap::{value_parser, Arg, ArgMatches, Command};
+use serde_json::Value;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const FORMATS: [&str; 4] = ["ccl", "json", "toml", "yaml"];
+
+pub fn command() -> Command {
+    Command::new("convert")
+        .about("Convert documents between CCL, JSON, TOML and YAML")
+        .arg(
+            Arg::new("from")
+                .long("from")
+                .value_name("FORMAT")
+                .value_parser(PossibleValuesParser::new(FORMATS))
+                .help("Format of the input, or the extension of the input file if not given"),
+        )
+        .arg(
+            Arg::new("to")
+                .long("to")
+                .value_name("FORMAT")
+                .required(true)
+                .value_parser(PossibleValuesParser::new(FORMATS))
+                .help("Format of the output"),
+        )
+        .arg(
+            Arg::new("file")
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+                .help("Document to convert, or standard input if not given"),
+        )
+}
+
+pub fn run(matches: &ArgMatches) -> ExitCode {
+    let path = matches.get_one::<PathBuf>("file");
+    let from = matches.get_one::<String>("from");
+    let to = matches.get_one::<String>("to").unwrap();
+
+    match convert(path.map(PathBuf::as_path), from.map(String::as_str), to) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn convert(path: Option<&Path>, from: Option<&str>, to: &str) -> Result<(), String> {
+    let from = match (from, path) {
+        (Some(from), _) => from,
+        (None, Some(path)) => format_of(path)?,
+        (None, None) => return Err("the format of standard input must be given with --from".into()),
+    };
+
+    let (name, text) = if let Some(path) = path {
+        let name = path.display().to_string();
+        let text = fs::read_to_string(path).map_err(|e| format!("{name}: {e}"))?;
+        (name, text)
+    } else {
+        let mut text = String::new();
+        io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|e| format!("<stdin>: {e}"))?;
+        ("<stdin>".into(), text)
+    };
+
+    let value = read(from, &name, &text)?;
+    let output = write(to, &value)?;
+
+    io::stdout()
+        .write_all(output.as_bytes())
+        .map_err(|e| format!("<stdout>: {e}"))
+}
+
+fn format_of(path: &Path) -> Result<&'static str, String> {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("ccl") => Ok("ccl"),
+        Some("json") => Ok("json"),
+        Some("toml") => Ok("toml"),
+        Some("yaml" | "yml") => Ok("yaml"),
+        _ => Err(format!(
+            "the format of {} must be given with --from",
+            path.display(),
+        )),
+    }
+}
+
+fn read(format: &str, name: &str, text: &str) -> Result<Value, String> {
+    match format {
+        "ccl" => {
+            let document =
+                serde_ccl::Document::parse_with_source(text, serde_ccl::Source::named(name))
+                    .map_err(|e| e.to_string())?;
+
+            serde_ccl::from_document(&document).map_err(|e| e.to_string())
+        }
+        "json" => serde_json::from_str(text).map_err(|e| format!("{name}: {e}")),
+        "toml" => toml::from_str(text)
+            .map(toml_to_json)
+            .map_err(|e| format!("{name}: {e}")),
+        "yaml" => serde_yaml::from_str(text).map_err(|e| format!("{name}: {e}")),
+        _ => unreachable!("clap only accepts known formats"),
+    }
+}
+
+fn write(format: &str, value: &Value) -> Result<String, String> {
+    match format {
+        "ccl" => serde_ccl::to_string(value).map_err(|e| e.to_string()),
+        "json" => serde_json::to_string_pretty(value)
+            .map(|json| json + "\n")
+            .map_err(|e| e.to_string()),
+        "toml" => toml::to_string(value).map_err(|e| e.to_string()),
+        "yaml" => serde_yaml::to_string(value).map_err(|e| e.to_string()),
+        _ => unreachable!("clap only accepts known formats"),
+    }
+}
+
+/// Converts a TOML value to JSON, writing dates and times as strings.
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(string) => Value::String(string),
+        toml::Value::Integer(integer) => Value::from(integer),
+        toml::Value::Float(float) => Value::from(float),
+        toml::Value::Boolean(boolean) => Value::Bool(boolean),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(array) => array.into_iter().map(toml_to_json).collect(),
+        toml::Value::Table(table) => table
+            .into_iter()
+            .map(|(key, value)| (key, toml_to_json(value)))
+            .collect(),
+    }
+}
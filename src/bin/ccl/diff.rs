@@ -0,0 +1,82 @@
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use serde_ccl::{ChangeKind, Document, Entry, Source};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+pub fn command() -> Command {
+    Command::new("diff")
+        .about("Print the keys that changed between two documents")
+        .arg(
+            Arg::new("exit-code")
+                .long("exit-code")
+                .action(ArgAction::SetTrue)
+                .help("Exit with a failure status if the documents differ"),
+        )
+        .arg(
+            Arg::new("old")
+                .value_name("OLD")
+                .required(true)
+                .value_parser(value_parser!(PathBuf))
+                .help("Old document"),
+        )
+        .arg(
+            Arg::new("new")
+                .value_name("NEW")
+                .required(true)
+                .value_parser(value_parser!(PathBuf))
+                .help("New document"),
+        )
+}
+
+pub fn run(matches: &ArgMatches) -> ExitCode {
+    let old = matches.get_one::<PathBuf>("old").unwrap();
+    let new = matches.get_one::<PathBuf>("new").unwrap();
+
+    match diff(old, new) {
+        Ok(true) if matches.get_flag("exit-code") => ExitCode::FAILURE,
+        Ok(_) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Prints the changes between the documents, returning whether there were
+/// any.
+fn diff(old: &Path, new: &Path) -> Result<bool, String> {
+    let old = read_document(old)?;
+    let new = read_document(new)?;
+    let changes = serde_ccl::diff(&old, &new);
+
+    for change in &changes {
+        match change.kind() {
+            ChangeKind::Added => {
+                println!("{change} ({})", location(change.new_entry().unwrap()));
+            }
+            ChangeKind::Removed => {
+                println!("{change} ({})", location(change.old_entry().unwrap()));
+            }
+            ChangeKind::Changed => println!(
+                "{change} ({} -> {})",
+                location(change.old_entry().unwrap()),
+                location(change.new_entry().unwrap()),
+            ),
+        }
+    }
+
+    Ok(!changes.is_empty())
+}
+
+fn read_document(path: &Path) -> Result<Document, String> {
+    let name = path.display().to_string();
+    let text = fs::read_to_string(path).map_err(|e| format!("{name}: {e}"))?;
+    Document::parse_with_source(&text, Source::named(&name)).map_err(|e| e.to_string())
+}
+
+/// Returns the file and line of the key of the entry.
+fn location(entry: &Entry) -> String {
+    let origin = entry.origin();
+    format!("{}:{}", origin.source(), origin.key_span().line())
+}
@@ -0,0 +1,103 @@
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use serde_ccl::Source;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+pub fn command() -> Command {
+    Command::new("fmt")
+        .about("Format CCL documents")
+        .arg(
+            Arg::new("check")
+                .long("check")
+                .action(ArgAction::SetTrue)
+                .help("List the documents that are not formatted instead of rewriting them"),
+        )
+        .arg(
+            Arg::new("files")
+                .value_name("FILE")
+                .num_args(0..)
+                .value_parser(value_parser!(PathBuf))
+                .help("Documents to format in place, or standard input if none are given"),
+        )
+}
+
+pub fn run(matches: &ArgMatches) -> ExitCode {
+    let check = matches.get_flag("check");
+
+    let Some(paths) = matches.get_many::<PathBuf>("files") else {
+        return format_stdin(check);
+    };
+
+    let mut code = ExitCode::SUCCESS;
+
+    for path in paths {
+        match format_file(path, check) {
+            Ok(true) if check => {
+                eprintln!("{} is not formatted", path.display());
+                code = ExitCode::FAILURE;
+            }
+            Ok(_) => (),
+            Err(error) => {
+                eprintln!("error: {error}");
+                code = ExitCode::FAILURE;
+            }
+        }
+    }
+
+    code
+}
+
+/// Formats the file at the given path, returning whether formatting changed
+/// it. The file is only rewritten if it changed and `check` is unset.
+fn format_file(path: &Path, check: bool) -> Result<bool, String> {
+    let name = path.display().to_string();
+    let text = fs::read_to_string(path).map_err(|e| format!("{name}: {e}"))?;
+
+    let formatted =
+        serde_ccl::format_with_source(&text, Source::named(&name)).map_err(|e| e.to_string())?;
+
+    if formatted == text {
+        return Ok(false);
+    }
+
+    if !check {
+        fs::write(path, formatted).map_err(|e| format!("{name}: {e}"))?;
+    }
+
+    Ok(true)
+}
+
+fn format_stdin(check: bool) -> ExitCode {
+    let mut text = String::new();
+
+    if let Err(error) = io::stdin().read_to_string(&mut text) {
+        eprintln!("error: <stdin>: {error}");
+        return ExitCode::FAILURE;
+    }
+
+    let formatted = match serde_ccl::format_with_source(&text, Source::named("<stdin>")) {
+        Ok(formatted) => formatted,
+        Err(error) => {
+            eprintln!("error: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if check {
+        if formatted == text {
+            return ExitCode::SUCCESS;
+        }
+
+        eprintln!("<stdin> is not formatted");
+        return ExitCode::FAILURE;
+    }
+
+    if let Err(error) = io::stdout().write_all(formatted.as_bytes()) {
+        eprintln!("error: <stdout>: {error}");
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,95 @@
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use serde_ccl::{Document, KeyPath, Source, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+pub fn command() -> Command {
+    Command::new("get")
+        .about("Print the value at a key path, such as `database.ports.1`")
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .action(ArgAction::SetTrue)
+                .help("Print the value as JSON instead of as written"),
+        )
+        .arg(
+            Arg::new("all")
+                .long("all")
+                .action(ArgAction::SetTrue)
+                .help("Print the values of all entries with the last key of the path"),
+        )
+        .arg(
+            Arg::new("file")
+                .value_name("FILE")
+                .required(true)
+                .value_parser(value_parser!(PathBuf))
+                .help("Document to read"),
+        )
+        .arg(
+            Arg::new("path")
+                .value_name("PATH")
+                .required(true)
+                .value_parser(value_parser!(KeyPath))
+                .help("Key path of the value"),
+        )
+}
+
+pub fn run(matches: &ArgMatches) -> ExitCode {
+    let file = matches.get_one::<PathBuf>("file").unwrap();
+    let path = matches.get_one::<KeyPath>("path").unwrap();
+
+    match get(
+        file,
+        path,
+        matches.get_flag("json"),
+        matches.get_flag("all"),
+    ) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn get(file: &Path, path: &KeyPath, json: bool, all: bool) -> Result<(), String> {
+    let name = file.display().to_string();
+    let text = fs::read_to_string(file).map_err(|e| format!("{name}: {e}"))?;
+
+    let document =
+        Document::parse_with_source(&text, Source::named(&name)).map_err(|e| e.to_string())?;
+
+    let mut entries = document.get_all_path(path);
+
+    if entries.is_empty() {
+        return Err(format!("no value at {path} in {name}"));
+    }
+
+    if json {
+        let mut values =
+            serde_ccl::get_all::<serde_json::Value>(&document, path).map_err(|e| e.to_string())?;
+
+        let json = if all {
+            serde_json::to_string_pretty(&values)
+        } else {
+            serde_json::to_string_pretty(&values.pop())
+        };
+
+        println!("{}", json.map_err(|e| e.to_string())?);
+        return Ok(());
+    }
+
+    if !all {
+        entries.drain(..entries.len() - 1);
+    }
+
+    for entry in entries {
+        match entry.value() {
+            Value::Text(text) => println!("{text}"),
+            Value::Block(block) => print!("{block}"),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use clap::builder::PossibleValuesParser;
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use serde_ccl::{ColumnMode, Diagnostic, LineIndex, Rule, Source};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+pub fn command() -> Command {
+    Command::new("lint")
+        .about("Check CCL documents for likely mistakes")
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(PossibleValuesParser::new(["human", "json", "sarif"]))
+                .default_value("human")
+                .help("Output format"),
+        )
+        .arg(
+            Arg::new("disable")
+                .long("disable")
+                .value_name("RULE")
+                .action(ArgAction::Append)
+                .value_parser(value_parser!(Rule))
+                .help("Rule to disable, such as `trailing-whitespace`"),
+        )
+        .arg(
+            Arg::new("files")
+                .value_name("FILE")
+                .required(true)
+                .num_args(1..)
+                .value_parser(value_parser!(PathBuf))
+                .help("Documents to check"),
+        )
+}
+
+/// Problems found in a document.
+struct Report {
+    name: String,
+    text: String,
+    diagnostics: Vec<Diagnostic>,
+}
+
+pub fn run(matches: &ArgMatches) -> ExitCode {
+    let disabled = matches
+        .get_many::<Rule>("disable")
+        .unwrap_or_default()
+        .collect::<Vec<_>>();
+
+    let mut code = ExitCode::SUCCESS;
+    let mut reports = Vec::new();
+
+    for path in matches.get_many::<PathBuf>("files").unwrap() {
+        match lint_file(path) {
+            Ok(mut report) => {
+                report
+                    .diagnostics
+                    .retain(|diagnostic| !disabled.contains(&&diagnostic.rule()));
+
+                if !report.diagnostics.is_empty() {
+                    code = ExitCode::FAILURE;
+                }
+
+                reports.push(report);
+            }
+            Err(error) => {
+                eprintln!("error: {error}");
+                code = ExitCode::FAILURE;
+            }
+        }
+    }
+
+    match matches.get_one::<String>("format").unwrap().as_str() {
+        "json" => println!("{:#}", to_json(&reports)),
+        "sarif" => println!("{:#}", to_sarif(&reports)),
+        _ => print_human(&reports),
+    }
+
+    code
+}
+
+fn lint_file(path: &Path) -> Result<Report, String> {
+    let name = path.display().to_string();
+    let text = fs::read_to_string(path).map_err(|e| format!("{name}: {e}"))?;
+
+    let diagnostics =
+        serde_ccl::lint_with_source(&text, Source::named(&name)).map_err(|e| e.to_string())?;
+
+    Ok(Report {
+        name,
+        text,
+        diagnostics,
+    })
+}
+
+fn print_human(reports: &[Report]) {
+    for report in reports {
+        for diagnostic in &report.diagnostics {
+            let span = diagnostic.span();
+
+            println!(
+                "{}:{}:{}: {} [{}]",
+                report.name,
+                span.line(),
+                span.column(),
+                diagnostic.message(),
+                diagnostic.rule(),
+            );
+        }
+    }
+}
+
+fn to_json(reports: &[Report]) -> Value {
+    let diagnostics = reports.iter().flat_map(|report| {
+        let line_index = LineIndex::new(&report.text);
+
+        report.diagnostics.iter().map(move |diagnostic| {
+            let span = diagnostic.span();
+            let end = line_index.position(span.end(), ColumnMode::Byte);
+
+            json!({
+                "file": report.name,
+                "rule": diagnostic.rule().name(),
+                "message": diagnostic.message(),
+                "line": span.line(),
+                "column": span.column(),
+                "end_line": end.line,
+                "end_column": end.column,
+            })
+        })
+    });
+
+    Value::Array(diagnostics.collect())
+}
+
+/// Builds a SARIF 2.1.0 log, with columns measured in UTF-16 code units as the
+/// format expects.
+fn to_sarif(reports: &[Report]) -> Value {
+    let rules = Rule::ALL.map(|rule| {
+        json!({
+            "id": rule.name(),
+            "shortDescription": { "text": rule.description() },
+        })
+    });
+
+    let results = reports.iter().flat_map(|report| {
+        let line_index = LineIndex::new(&report.text);
+
+        report.diagnostics.iter().map(move |diagnostic| {
+            let span = diagnostic.span();
+            let end = line_index.position(span.end(), ColumnMode::Utf16);
+            let rule = diagnostic.rule();
+
+            json!({
+                "ruleId": rule.name(),
+                "ruleIndex": Rule::ALL.iter().position(|r| *r == rule),
+                "level": "warning",
+                "message": { "text": diagnostic.message() },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": report.name.replace('\\', "/") },
+                        "region": {
+                            "startLine": span.line(),
+                            "startColumn": span.column_utf16(),
+                            "endLine": end.line,
+                            "endColumn": end.column,
+                        },
+                    },
+                }],
+            })
+        })
+    });
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "ccl",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                },
+            },
+            "columnKind": "utf16CodeUnits",
+            "results": results.collect::<Vec<_>>(),
+        }],
+    })
+}
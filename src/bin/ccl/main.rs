@@ -0,0 +1,45 @@
+//! Command-line tool for working with CCL documents.
+
+mod convert;
+mod diff;
+mod fmt;
+mod get;
+mod lint;
+mod merge;
+mod set;
+mod unset;
+mod validate;
+
+use clap::Command;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let matches = Command::new("ccl")
+        .about("Work with CCL documents")
+        .version(env!("CARGO_PKG_VERSION"))
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(convert::command())
+        .subcommand(diff::command())
+        .subcommand(fmt::command())
+        .subcommand(get::command())
+        .subcommand(lint::command())
+        .subcommand(merge::command())
+        .subcommand(set::command())
+        .subcommand(unset::command())
+        .subcommand(validate::command())
+        .get_matches();
+
+    match matches.subcommand() {
+        Some(("convert", matches)) => convert::run(matches),
+        Some(("diff", matches)) => diff::run(matches),
+        Some(("fmt", matches)) => fmt::run(matches),
+        Some(("get", matches)) => get::run(matches),
+        Some(("lint", matches)) => lint::run(matches),
+        Some(("merge", matches)) => merge::run(matches),
+        Some(("set", matches)) => set::run(matches),
+        Some(("unset", matches)) => unset::run(matches),
+        Some(("validate", matches)) => validate::run(matches),
+        _ => unreachable!("clap requires a known subcommand"),
+    }
+}
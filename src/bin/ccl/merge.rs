@@ -0,0 +1,59 @@
+use clap::builder::PossibleValuesParser;
+use clap::{value_parser, Arg, ArgMatches, Command};
+use serde_ccl::{Document, Layers, ListPolicy, MergeOptions, Source};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+pub fn command() -> Command {
+    Command::new("merge")
+        .about("Merge documents and print the result, with later documents taking precedence")
+        .arg(
+            Arg::new("lists")
+                .long("lists")
+                .value_name("POLICY")
+                .value_parser(PossibleValuesParser::new(["append", "replace"]))
+                .default_value("append")
+                .help("Whether list items of later documents are appended or replace earlier ones"),
+        )
+        .arg(
+            Arg::new("files")
+                .value_name("FILE")
+                .required(true)
+                .num_args(1..)
+                .value_parser(value_parser!(PathBuf))
+                .help("Documents to merge, from lowest to highest precedence"),
+        )
+}
+
+pub fn run(matches: &ArgMatches) -> ExitCode {
+    let list_policy = match matches.get_one::<String>("lists").unwrap().as_str() {
+        "replace" => ListPolicy::Replace,
+        _ => ListPolicy::Append,
+    };
+
+    let mut layers = Layers::new().with_options(MergeOptions::new().with_list_policy(list_policy));
+
+    for path in matches.get_many::<PathBuf>("files").unwrap() {
+        match read_document(path) {
+            Ok((name, document)) => layers.push(name, document),
+            Err(error) => {
+                eprintln!("error: {error}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    print!("{}", layers.into_document());
+    ExitCode::SUCCESS
+}
+
+fn read_document(path: &Path) -> Result<(String, Document), String> {
+    let name = path.display().to_string();
+    let text = fs::read_to_string(path).map_err(|e| format!("{name}: {e}"))?;
+
+    let document =
+        Document::parse_with_source(&text, Source::named(&name)).map_err(|e| e.to_string())?;
+
+    Ok((name, document))
+}
@@ -0,0 +1,55 @@
+use clap::{value_parser, Arg, ArgMatches, Command};
+use serde_ccl::{Editor, KeyPath, Source};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+pub fn command() -> Command {
+    Command::new("set")
+        .about("Set the value at a key path, keeping the rest of the document as written")
+        .arg(
+            Arg::new("file")
+                .value_name("FILE")
+                .required(true)
+                .value_parser(value_parser!(PathBuf))
+                .help("Document to edit in place"),
+        )
+        .arg(
+            Arg::new("path")
+                .value_name("PATH")
+                .required(true)
+                .value_parser(value_parser!(KeyPath))
+                .help("Key path of the value, created if missing"),
+        )
+        .arg(
+            Arg::new("value")
+                .value_name("VALUE")
+                .required(true)
+                .help("New value"),
+        )
+}
+
+pub fn run(matches: &ArgMatches) -> ExitCode {
+    let file = matches.get_one::<PathBuf>("file").unwrap();
+    let path = matches.get_one::<KeyPath>("path").unwrap();
+    let value = matches.get_one::<String>("value").unwrap();
+
+    match set(file, path, value) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn set(file: &Path, path: &KeyPath, value: &str) -> Result<(), String> {
+    let name = file.display().to_string();
+    let text = fs::read_to_string(file).map_err(|e| format!("{name}: {e}"))?;
+
+    let mut editor =
+        Editor::parse_with_source(&text, Source::named(&name)).map_err(|e| e.to_string())?;
+
+    editor.set(path, value).map_err(|e| e.to_string())?;
+    fs::write(file, editor.text()).map_err(|e| format!("{name}: {e}"))
+}
@@ -0,0 +1,93 @@
+use clap::{value_parser, Arg, ArgMatches, Command};
+use serde_ccl::{Document, Schema, Source};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+pub fn command() -> Command {
+    Command::new("validate")
+        .about("Check CCL documents against a JSON Schema")
+        .arg(
+            Arg::new("schema")
+                .long("schema")
+                .value_name("SCHEMA")
+                .required(true)
+                .value_parser(value_parser!(PathBuf))
+                .help("JSON Schema to check against"),
+        )
+        .arg(
+            Arg::new("files")
+                .value_name("FILE")
+                .required(true)
+                .num_args(1..)
+                .value_parser(value_parser!(PathBuf))
+                .help("Documents to check"),
+        )
+}
+
+pub fn run(matches: &ArgMatches) -> ExitCode {
+    let schema = match load_schema(matches.get_one::<PathBuf>("schema").unwrap()) {
+        Ok(schema) => schema,
+        Err(error) => {
+            eprintln!("error: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut code = ExitCode::SUCCESS;
+
+    for path in matches.get_many::<PathBuf>("files").unwrap() {
+        match validate_file(&schema, path) {
+            Ok(true) => (),
+            Ok(false) => code = ExitCode::FAILURE,
+            Err(error) => {
+                eprintln!("error: {error}");
+                code = ExitCode::FAILURE;
+            }
+        }
+    }
+
+    code
+}
+
+fn load_schema(path: &Path) -> Result<Schema, String> {
+    let name = path.display();
+    let text = fs::read_to_string(path).map_err(|e| format!("{name}: {e}"))?;
+    let json = serde_json::from_str(&text).map_err(|e| format!("{name}: {e}"))?;
+    Schema::new(json).map_err(|e| format!("{name}: {e}"))
+}
+
+/// Validates the file at the given path, printing the violations and returning
+/// whether there were none.
+fn validate_file(schema: &Schema, path: &Path) -> Result<bool, String> {
+    let name = path.display().to_string();
+    let text = fs::read_to_string(path).map_err(|e| format!("{name}: {e}"))?;
+
+    let document =
+        Document::parse_with_source(&text, Source::named(&name)).map_err(|e| e.to_string())?;
+
+    let violations = schema.validate(&document);
+
+    for violation in &violations {
+        let span = violation.span();
+        let path = violation.path();
+
+        if path.segments().is_empty() {
+            println!(
+                "{name}:{}:{}: {}",
+                span.line(),
+                span.column(),
+                violation.message()
+            );
+        } else {
+            println!(
+                "{name}:{}:{}: {path}: {}",
+                span.line(),
+                span.column(),
+                violation.message(),
+            );
+        }
+    }
+
+    Ok(violations.is_empty())
+}
@@ -1,7 +1,12 @@
+use crate::document::{Document, Entry, Value};
 use crate::error::{Error, ErrorCode, Result};
 use crate::parser::{IndentState, Parser};
-use core::str::FromStr;
+use crate::position::Location;
+use crate::raw;
+use crate::spanned::{self, Span};
+use core::str::{self, FromStr};
 use serde_core::de;
+use serde_core::de::value::{BorrowedStrDeserializer, StrDeserializer, UsizeDeserializer};
 
 #[must_use]
 pub(crate) struct Deserializer<P> {
@@ -29,6 +34,11 @@ where
     }
 
     fn parse(&mut self) -> Result<&'a str> {
+        if self.is_first {
+            self.is_first = false;
+            return self.parser.parse_remaining();
+        }
+
         match self.next_to_parse {
             ElemType::Key => self.parser.parse_key(),
             ElemType::Value => self.parser.parse_value(),
@@ -44,7 +54,7 @@ where
 
         T::from_str(value).map_err(|_| {
             Error::new(error(), unsafe {
-                self.parser.position_of_ptr(value.as_ptr())
+                self.parser.location_of_ptr(value.as_ptr())
             })
         })
     }
@@ -73,11 +83,84 @@ where
 
     #[must_use]
     fn locate_error(&self, error: Error, last_key_index: usize) -> Error {
-        if !error.position().is_default() {
+        if !error.location().is_default() {
             return error;
         }
 
-        error.with_position(self.parser.position_of_index(last_key_index))
+        error.with_location(self.parser.location_of_index(last_key_index))
+    }
+
+    fn parse_raw(&mut self) -> Result<&'a str> {
+        let is_key = !self.is_first && matches!(self.next_to_parse, ElemType::Key);
+        let raw = self.parse()?;
+
+        if is_key || raw.is_empty() {
+            return Ok(raw);
+        }
+
+        // Include the indentation of values that start on a new line so the
+        // raw value can be parsed again as a document.
+        let data = self.parser.data();
+        let start = unsafe { self.parser.index_of_ptr(raw.as_ptr()) };
+
+        let indent_start = data[..start]
+            .iter()
+            .rposition(|&byte| byte != b' ')
+            .map_or(0, |index| index + 1);
+
+        if indent_start != 0 && data[indent_start - 1] != b'\n' {
+            return Ok(raw);
+        }
+
+        unsafe {
+            Ok(str::from_utf8_unchecked(
+                &data[indent_start..start + raw.len()],
+            ))
+        }
+    }
+
+    fn deserialize_spanned<V>(&mut self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'a>,
+    {
+        let (key_span, value_start) = if self.is_first {
+            let start = match self.parser.skip_whitespace()? {
+                IndentState::Eof => self.parser.data().len(),
+                _ => self.parser.index(),
+            };
+
+            (Some(Span::new(self.parser.location_of_index(0), 0)), start)
+        } else {
+            match self.next_to_parse {
+                ElemType::Key => (None, 0),
+                ElemType::Value => {
+                    let key_span = Span::new(
+                        self.parser.location_of_index(self.parser.last_key_index()),
+                        self.parser.last_key_end(),
+                    );
+
+                    let start = self.parser.index();
+
+                    let start = match self.parser.skip_whitespace()? {
+                        IndentState::Start(indent) if indent > self.parser.last_key_indent() => {
+                            self.parser.index()
+                        }
+                        IndentState::Middle => self.parser.index(),
+                        _ => start,
+                    };
+
+                    (Some(key_span), start)
+                }
+            }
+        };
+
+        visitor.visit_map(SpannedAccess {
+            value_start: self.parser.location_of_index(value_start),
+            de: self,
+            key_span,
+            fields: [0; 12],
+            field: 0,
+        })
     }
 }
 
@@ -177,6 +260,62 @@ where
     }
 }
 
+#[must_use]
+struct SpannedAccess<'a, P> {
+    de: &'a mut Deserializer<P>,
+    key_span: Option<Span>,
+    value_start: Location,
+    fields: [usize; 12],
+    field: usize,
+}
+
+impl<'de, P> de::MapAccess<'de> for SpannedAccess<'_, P>
+where
+    P: Parser<'de>,
+{
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        let Some(&field) = spanned::FIELDS.get(self.field) else {
+            return Ok(None);
+        };
+
+        seed.deserialize(BorrowedStrDeserializer::new(field))
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        self.field += 1;
+
+        if self.field != 1 {
+            let field = self.fields[self.field - 2];
+            return seed.deserialize(UsizeDeserializer::new(field));
+        }
+
+        let value = seed.deserialize(&mut *self.de)?;
+        let parser = &self.de.parser;
+
+        let (key_span, value_span) = if let Some(key_span) = self.key_span {
+            let value_end = parser.last_value_end();
+            (key_span, Span::new(self.value_start, value_end))
+        } else {
+            let key_start = parser.location_of_index(parser.last_key_index());
+            let key_span = Span::new(key_start, parser.last_key_end());
+            (key_span, key_span)
+        };
+
+        self.fields[..6].copy_from_slice(&key_span.to_fields());
+        self.fields[6..].copy_from_slice(&value_span.to_fields());
+        Ok(value)
+    }
+}
+
 impl<'de, 'a, P> de::EnumAccess<'de> for KeyValueAccess<'a, P>
 where
     P: Parser<'de> + 'a,
@@ -239,7 +378,29 @@ where
     where
         V: de::Visitor<'de>,
     {
-        self.deserialize_str(visitor)
+        if !self.is_first && matches!(self.next_to_parse, ElemType::Key) {
+            return self.deserialize_str(visitor);
+        }
+
+        let is_first = self.is_first;
+        let last_key_index = self.parser.last_key_index();
+        let raw = self.parse_raw()?;
+        let text = raw.trim_start_matches(' ');
+
+        // Like in a `Document`, the document and values that start on the line
+        // following their key are nested entries when they can be parsed as
+        // such.
+        if is_first || text.len() != raw.len() {
+            if let Ok(block) = Document::parse(raw) {
+                if has_entries(&block) {
+                    return BlockDeserializer(&block)
+                        .deserialize_any(visitor)
+                        .map_err(|e| self.locate_error(e, last_key_index));
+                }
+            }
+        }
+
+        visitor.visit_borrowed_str(text)
     }
 
     fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
@@ -385,10 +546,14 @@ where
         self.deserialize_unit(visitor)
     }
 
-    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
+        if name == raw::TOKEN {
+            return visitor.visit_borrowed_str(self.parse_raw()?);
+        }
+
         visitor.visit_newtype_struct(self)
     }
 
@@ -435,13 +600,17 @@ where
 
     fn deserialize_struct<V>(
         self,
-        _name: &'static str,
-        _fields: &'static [&'static str],
+        name: &'static str,
+        fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
+        if name == spanned::NAME && fields == spanned::FIELDS {
+            return self.deserialize_spanned(visitor);
+        }
+
         self.deserialize_map(visitor)
     }
 
@@ -472,6 +641,133 @@ where
     where
         V: de::Visitor<'de>,
     {
-        self.deserialize_any(visitor)
+        self.parse()?;
+        visitor.visit_unit()
+    }
+}
+
+/// Deserializes the entries of a block in [`deserialize_any`], as a sequence
+/// if all their keys are empty and as a map otherwise. Comments are skipped.
+///
+/// [`deserialize_any`]: de::Deserializer::deserialize_any
+struct BlockDeserializer<'a>(&'a Document);
+
+impl<'de> de::Deserializer<'de> for BlockDeserializer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let access = BlockAccess {
+            entries: self.0.iter(),
+            value: None,
+        };
+
+        if self
+            .0
+            .iter()
+            .all(|entry| is_comment(entry) || entry.key().is_empty())
+        {
+            visitor.visit_seq(access)
+        } else {
+            visitor.visit_map(access)
+        }
     }
+
+    serde_core::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// Deserializes the value of an entry of a block in [`deserialize_any`].
+///
+/// [`deserialize_any`]: de::Deserializer::deserialize_any
+struct EntryValueDeserializer<'a>(&'a Value);
+
+impl<'de> de::Deserializer<'de> for EntryValueDeserializer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.0 {
+            Value::Block(block) if has_entries(block) => {
+                BlockDeserializer(block).deserialize_any(visitor)
+            }
+            Value::Block(_) => visitor.visit_str(""),
+            Value::Text(text) => visitor.visit_str(text),
+        }
+    }
+
+    serde_core::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct BlockAccess<'a> {
+    entries: core::slice::Iter<'a, Entry>,
+    value: Option<&'a Value>,
+}
+
+impl<'a> BlockAccess<'a> {
+    fn next_entry(&mut self) -> Option<&'a Entry> {
+        self.entries.by_ref().find(|entry| !is_comment(entry))
+    }
+}
+
+impl<'de> de::MapAccess<'de> for BlockAccess<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        let Some(entry) = self.next_entry() else {
+            return Ok(None);
+        };
+
+        self.value = Some(entry.value());
+        seed.deserialize(StrDeserializer::new(entry.key()))
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let Some(value) = self.value.take() else {
+            return Err(de::Error::custom("value requested before key"));
+        };
+
+        seed.deserialize(EntryValueDeserializer(value))
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for BlockAccess<'_> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        self.next_entry()
+            .map(|entry| seed.deserialize(EntryValueDeserializer(entry.value())))
+            .transpose()
+    }
+}
+
+#[must_use]
+fn is_comment(entry: &Entry) -> bool {
+    entry.key() == "/"
+}
+
+#[must_use]
+fn has_entries(block: &Document) -> bool {
+    !block.iter().all(is_comment)
 }
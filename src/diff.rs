@@ -0,0 +1,218 @@
+use crate::document::{Document, Entry, Value};
+use crate::path::{KeyPath, Segment};
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Kind of a [`Change`] between two documents.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ChangeKind {
+    /// The entry only exists in the new document.
+    Added,
+
+    /// The entry only exists in the old document.
+    Removed,
+
+    /// The entry exists in both documents with different values.
+    Changed,
+}
+
+/// Difference between an entry of the old document and an entry of the new
+/// document, returned by [`diff`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Change<'a> {
+    path: KeyPath,
+    old: Option<&'a Entry>,
+    new: Option<&'a Entry>,
+}
+
+impl<'a> Change<'a> {
+    /// Returns the kind of the change.
+    #[must_use]
+    pub fn kind(&self) -> ChangeKind {
+        match (self.old, self.new) {
+            (None, _) => ChangeKind::Added,
+            (_, None) => ChangeKind::Removed,
+            _ => ChangeKind::Changed,
+        }
+    }
+
+    /// Returns the path of the changed entry.
+    #[inline]
+    #[must_use]
+    pub fn path(&self) -> &KeyPath {
+        &self.path
+    }
+
+    /// Returns the entry in the old document, unless it was added.
+    #[inline]
+    #[must_use]
+    pub fn old_entry(&self) -> Option<&'a Entry> {
+        self.old
+    }
+
+    /// Returns the entry in the new document, unless it was removed.
+    #[inline]
+    #[must_use]
+    pub fn new_entry(&self) -> Option<&'a Entry> {
+        self.new
+    }
+}
+
+impl fmt::Display for Change<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind() {
+            ChangeKind::Added => write!(f, "added {}", self.path),
+            ChangeKind::Removed => write!(f, "removed {}", self.path),
+            ChangeKind::Changed => write!(f, "changed {}", self.path),
+        }
+    }
+}
+
+/// Returns the semantic differences between two documents.
+///
+/// Entries are compared by key, with the last entry taking precedence when a
+/// key is repeated, and list items, which have empty keys, are compared by
+/// index. Comments and the indentation of values spanning multiple lines are
+/// ignored. Added and removed blocks are reported as a single change.
+///
+/// ```
+/// use serde_ccl::{ChangeKind, Document};
+///
+/// let old = Document::parse("port = 80\nhosts =\n  = a.com\n").unwrap();
+/// let new = Document::parse("/= Changed port\nport = 8080\nhosts =\n    = a.com\n    = b.com\n").unwrap();
+///
+/// let changes = serde_ccl::diff(&old, &new);
+/// assert_eq!(changes.len(), 2);
+///
+/// assert_eq!(changes[0].kind(), ChangeKind::Changed);
+/// assert_eq!(changes[0].path().to_string(), "port");
+/// assert_eq!(changes[0].new_entry().unwrap().origin().key_span().line(), 2);
+///
+/// assert_eq!(changes[1].to_string(), "added hosts[1]");
+/// ```
+#[must_use]
+pub fn diff<'a>(old: &'a Document, new: &'a Document) -> Vec<Change<'a>> {
+    let mut changes = Vec::new();
+    diff_documents(old, new, &mut Vec::new(), &mut changes);
+    changes
+}
+
+fn diff_documents<'a>(
+    old: &'a Document,
+    new: &'a Document,
+    path: &mut Vec<Segment>,
+    changes: &mut Vec<Change<'a>>,
+) {
+    let old_keys = keys(old);
+    let new_keys = keys(new);
+
+    for &key in &old_keys {
+        path.push(Segment::Key(key.into()));
+        diff_entries(old.get(key), new.get(key), path, changes);
+        path.pop();
+    }
+
+    for &key in new_keys.iter().filter(|key| !old_keys.contains(key)) {
+        path.push(Segment::Key(key.into()));
+        diff_entries(None, new.get(key), path, changes);
+        path.pop();
+    }
+
+    let mut old_items = items(old);
+    let mut new_items = items(new);
+    let mut index = 0;
+
+    loop {
+        let (old_item, new_item) = (old_items.next(), new_items.next());
+
+        if old_item.is_none() && new_item.is_none() {
+            break;
+        }
+
+        path.push(Segment::Index(index));
+        diff_entries(old_item, new_item, path, changes);
+        path.pop();
+        index += 1;
+    }
+}
+
+fn diff_entries<'a>(
+    old: Option<&'a Entry>,
+    new: Option<&'a Entry>,
+    path: &mut Vec<Segment>,
+    changes: &mut Vec<Change<'a>>,
+) {
+    let is_changed = match (old, new) {
+        (Some(old), Some(new)) => match (old.value(), new.value()) {
+            (Value::Block(old), Value::Block(new)) => {
+                diff_documents(old, new, path, changes);
+                false
+            }
+            (old, new) => normalize(old) != normalize(new),
+        },
+        _ => true,
+    };
+
+    if is_changed {
+        changes.push(Change {
+            path: path.iter().cloned().collect(),
+            old,
+            new,
+        });
+    }
+}
+
+/// Returns the distinct keys of the document in the order of their first
+/// occurrence, ignoring list items and comments.
+fn keys(document: &Document) -> Vec<&str> {
+    let mut keys = Vec::<&str>::new();
+
+    for entry in document {
+        let key = entry.key();
+
+        if !(key.is_empty() || key == "/" || keys.contains(&key)) {
+            keys.push(key);
+        }
+    }
+
+    keys
+}
+
+fn items(document: &Document) -> impl Iterator<Item = &Entry> {
+    document.iter().filter(|entry| entry.key().is_empty())
+}
+
+/// Returns the text of a value with the common indentation of its
+/// continuation lines removed, or `None` for blocks with entries other than
+/// comments.
+fn normalize(value: &Value) -> Option<Cow<'_, str>> {
+    let text = match value {
+        Value::Text(text) => text,
+        Value::Block(block) if block.iter().all(|entry| entry.key() == "/") => {
+            return Some(Cow::Borrowed(""));
+        }
+        Value::Block(_) => return None,
+    };
+
+    let Some((first_line, rest)) = text.split_once('\n') else {
+        return Some(Cow::Borrowed(text));
+    };
+
+    let indent = rest
+        .split('\n')
+        .filter(|line| !line.trim_start_matches(' ').is_empty())
+        .map(|line| line.len() - line.trim_start_matches(' ').len())
+        .min()
+        .unwrap_or(0);
+
+    let mut normalized = String::from(first_line);
+
+    for line in rest.split('\n') {
+        normalized.push('\n');
+        normalized.push_str(line.get(indent..).unwrap_or_default());
+    }
+
+    Some(Cow::Owned(normalized))
+}
@@ -0,0 +1,1097 @@
+use crate::error::{Error, ErrorCode, Result};
+use crate::interpolate::{interpolate_document, resolve_document_references, Lookup};
+use crate::line_index::LineIndex;
+use crate::merge::{ListPolicy, MergeOptions};
+use crate::parser::{IndentState, Parser, SliceParser};
+use crate::path::{get_all_path, set_path, KeyPath};
+use crate::position::Location;
+use crate::spanned::Span;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+use core::{fmt, str};
+use serde_core::de::DeserializeOwned;
+
+/// Number of spaces used to indent nested entries when rendering a document.
+pub(crate) const INDENT: usize = 4;
+
+/// Input from which the entries of a document were parsed.
+#[derive(Clone, PartialEq, Eq, Hash, Default, Debug)]
+#[non_exhaustive]
+pub enum Source {
+    /// Input without a name, such as a string passed to [`Document::parse`].
+    #[default]
+    Anonymous,
+
+    /// Input with a name, such as the path of a file.
+    Named(Arc<str>),
+
+    /// Environment variable with the given name.
+    Environment(Arc<str>),
+
+    /// Command-line argument with the given text.
+    Argument(Arc<str>),
+}
+
+impl Source {
+    /// Creates a source with the given name.
+    #[must_use]
+    pub fn named(name: &str) -> Self {
+        Self::Named(name.into())
+    }
+
+    /// Creates a source for the environment variable with the given name.
+    #[must_use]
+    pub fn environment(variable: &str) -> Self {
+        Self::Environment(variable.into())
+    }
+
+    /// Creates a source for the command-line argument with the given text.
+    #[must_use]
+    pub fn argument(arg: &str) -> Self {
+        Self::Argument(arg.into())
+    }
+
+    /// Returns the name of the source, if it has one.
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Self::Anonymous | Self::Environment(_) | Self::Argument(_) => None,
+            Self::Named(name) => Some(name),
+        }
+    }
+
+    /// Returns the name of the environment variable, if the source is one.
+    #[must_use]
+    pub fn variable(&self) -> Option<&str> {
+        match self {
+            Self::Environment(variable) => Some(variable),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Anonymous => f.write_str("<anonymous>"),
+            Self::Named(name) => f.write_str(name),
+            Self::Environment(variable) => write!(f, "environment variable {variable}"),
+            Self::Argument(arg) => write!(f, "argument `{arg}`"),
+        }
+    }
+}
+
+/// Location of an entry in the input it was parsed from.
+///
+/// Entries created programmatically have an anonymous source and empty spans at
+/// line 0.
+#[derive(Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub struct Origin {
+    source: Source,
+    key_span: Span,
+    value_span: Span,
+}
+
+impl Origin {
+    /// Creates a new origin from the given source and spans.
+    #[must_use]
+    pub fn new(source: Source, key_span: Span, value_span: Span) -> Self {
+        Self {
+            source,
+            key_span,
+            value_span,
+        }
+    }
+
+    /// Returns the input from which the entry was parsed.
+    #[inline]
+    #[must_use]
+    pub fn source(&self) -> &Source {
+        &self.source
+    }
+
+    /// Returns the span of the key in the input.
+    #[inline]
+    #[must_use]
+    pub fn key_span(&self) -> Span {
+        self.key_span
+    }
+
+    /// Returns the span of the value in the input.
+    #[inline]
+    #[must_use]
+    pub fn value_span(&self) -> Span {
+        self.value_span
+    }
+
+    /// Returns the end of the last line of the entry in the input, without the
+    /// newline.
+    #[inline]
+    #[must_use]
+    pub fn end(&self) -> usize {
+        self.value_span.end().max(self.key_span.end())
+    }
+
+    /// Returns whether the entry was parsed from an input, as opposed to being
+    /// created programmatically.
+    #[inline]
+    #[must_use]
+    pub fn is_parsed(&self) -> bool {
+        self.key_span.line() != 0
+    }
+}
+
+/// Value of an entry in a [`Document`].
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Value {
+    /// Value written on the same line as its key, or a value spanning
+    /// multiple lines that does not contain entries.
+    Text(String),
+
+    /// Nested entries written on the lines following the key.
+    Block(Document),
+}
+
+impl Value {
+    /// Returns the text of the value, if it is not a block.
+    #[must_use]
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Self::Text(text) => Some(text),
+            Self::Block(_) => None,
+        }
+    }
+
+    /// Returns the nested entries of the value, if it is a block.
+    #[must_use]
+    pub fn as_block(&self) -> Option<&Document> {
+        match self {
+            Self::Text(_) => None,
+            Self::Block(block) => Some(block),
+        }
+    }
+
+    /// Returns the nested entries of the value, if it is a block.
+    #[must_use]
+    pub fn as_block_mut(&mut self) -> Option<&mut Document> {
+        match self {
+            Self::Text(_) => None,
+            Self::Block(block) => Some(block),
+        }
+    }
+
+    /// Returns whether the value is empty text or a block without entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::Text(text) => text.is_empty(),
+            Self::Block(block) => block.is_empty(),
+        }
+    }
+}
+
+impl Default for Value {
+    fn default() -> Self {
+        Self::Text(String::new())
+    }
+}
+
+impl From<String> for Value {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(text: &str) -> Self {
+        Self::Text(text.into())
+    }
+}
+
+impl From<Document> for Value {
+    fn from(block: Document) -> Self {
+        Self::Block(block)
+    }
+}
+
+/// Key-value pair in a [`Document`].
+///
+/// Comparing and hashing entries only takes the key and value into account.
+#[derive(Clone, Debug)]
+pub struct Entry {
+    key: String,
+    value: Value,
+    origin: Origin,
+}
+
+impl Entry {
+    /// Creates a new entry without an origin.
+    #[must_use]
+    pub fn new<K, V>(key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<Value>,
+    {
+        Self {
+            key: key.into(),
+            value: value.into(),
+            origin: Origin::default(),
+        }
+    }
+
+    /// Sets the origin of the entry.
+    #[must_use]
+    pub fn with_origin(mut self, origin: Origin) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Returns the key of the entry.
+    #[inline]
+    #[must_use]
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the value of the entry.
+    #[inline]
+    #[must_use]
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    /// Returns a mutable reference to the value of the entry.
+    #[inline]
+    #[must_use]
+    pub fn value_mut(&mut self) -> &mut Value {
+        &mut self.value
+    }
+
+    /// Returns the location of the entry in the input it was parsed from.
+    #[inline]
+    #[must_use]
+    pub fn origin(&self) -> &Origin {
+        &self.origin
+    }
+
+    /// Returns a mutable reference to the origin of the entry.
+    #[inline]
+    #[must_use]
+    pub fn origin_mut(&mut self) -> &mut Origin {
+        &mut self.origin
+    }
+
+    /// Consumes the entry and returns its key and value.
+    #[must_use]
+    pub fn into_parts(self) -> (String, Value) {
+        (self.key, self.value)
+    }
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key && self.value == other.value
+    }
+}
+
+impl Eq for Entry {
+    // Empty
+}
+
+impl core::hash::Hash for Entry {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: core::hash::Hasher,
+    {
+        self.key.hash(state);
+        self.value.hash(state);
+    }
+}
+
+/// Parsed CCL document that keeps track of where each entry came from.
+///
+/// Values that start on the line following their key are parsed as nested
+/// entries when possible and kept as text otherwise. Nested entries remember
+/// the text they were parsed from until they are modified, so deserializing
+/// them as text with [`from_document`] gives the same result as
+/// [`from_str`](crate::from_str). Use [`Display`](fmt::Display) to write a
+/// document back as CCL.
+///
+/// Keys that span multiple lines, which happen when a line has no equal sign,
+/// are an error.
+///
+/// [`from_document`]: crate::from_document
+///
+/// ```
+/// use serde_ccl::{Document, Value};
+///
+/// let document = Document::parse("name = app\nports =\n  = 80\n  = 443\n").unwrap();
+///
+/// let ports = document.get("ports").unwrap().value().as_block().unwrap();
+/// assert_eq!(ports.len(), 2);
+/// assert_eq!(ports.entries()[1].value(), &Value::from("443"));
+/// ```
+#[derive(Clone, Default)]
+pub struct Document {
+    entries: Vec<Entry>,
+    raw: Option<Raw>,
+}
+
+/// Text a block was parsed from.
+#[derive(Clone)]
+struct Raw {
+    input: Arc<str>,
+    span: Span,
+}
+
+impl Raw {
+    #[must_use]
+    fn text(&self) -> &str {
+        &self.input[self.span.range()]
+    }
+}
+
+impl Document {
+    /// Creates an empty document.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            raw: None,
+        }
+    }
+
+    /// Parses a document from a string.
+    pub fn parse(text: &str) -> Result<Self> {
+        Self::parse_with_source(text, Source::Anonymous)
+    }
+
+    /// Parses a document from a string, recording the given source as the
+    /// origin of its entries.
+    pub fn parse_with_source(text: &str, source: Source) -> Result<Self> {
+        let line_index = LineIndex::new(text);
+
+        DocumentParser::new(text, &line_index, &source, false)
+            .parse_block(0, text.len(), false)
+            .map_err(|e| e.with_input(source))
+    }
+
+    /// Parses a document, skipping the lines of keys that span multiple lines
+    /// and the lines without an equal sign at the end of blocks instead of
+    /// failing, and returns them.
+    pub(crate) fn parse_recovering(text: &str, source: Source) -> Result<(Self, Vec<MissingEq>)> {
+        let line_index = LineIndex::new(text);
+        let mut parser = DocumentParser::new(text, &line_index, &source, true);
+        let document = parser.parse_block(0, text.len(), false);
+        let missing_eqs = parser.missing_eqs;
+
+        match document {
+            Ok(document) => Ok((document, missing_eqs)),
+            Err(e) => Err(e.with_input(source)),
+        }
+    }
+
+    /// Returns the entries of the document.
+    #[inline]
+    #[must_use]
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Returns a mutable reference to the entries of the document.
+    #[inline]
+    #[must_use]
+    pub fn entries_mut(&mut self) -> &mut Vec<Entry> {
+        self.raw = None;
+        &mut self.entries
+    }
+
+    /// Returns an iterator over the entries of the document.
+    #[inline]
+    pub fn iter(&self) -> core::slice::Iter<'_, Entry> {
+        self.entries.iter()
+    }
+
+    /// Returns the number of entries in the document.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether the document has no entries.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns whether the document is a list, meaning that all its entries
+    /// are list items or comments and at least one is a list item.
+    #[must_use]
+    pub fn is_list(&self) -> bool {
+        self.iter().any(|entry| entry.key().is_empty())
+            && self.iter().all(|entry| matches!(entry.key(), "" | "/"))
+    }
+
+    /// Appends an entry to the document.
+    #[inline]
+    pub fn push(&mut self, entry: Entry) {
+        self.raw = None;
+        self.entries.push(entry);
+    }
+
+    /// Merges the entries of another document into this one with the default
+    /// [`MergeOptions`].
+    ///
+    /// Each entry replaces the last existing entry with the same key, unless
+    /// both values are blocks, in which case the blocks are merged
+    /// recursively. Entries with empty keys, which are list items, and
+    /// comments are appended.
+    pub fn merge(&mut self, other: Document) {
+        self.merge_with(other, MergeOptions::default());
+    }
+
+    /// Merges the entries of another document into this one, as described in
+    /// [`merge`](Self::merge), using the given options.
+    pub fn merge_with(&mut self, other: Document, options: MergeOptions) {
+        self.raw = None;
+
+        if options.list_policy() == ListPolicy::Replace
+            && other.entries.iter().any(|entry| entry.key.is_empty())
+        {
+            self.entries.retain(|entry| !entry.key.is_empty());
+        }
+
+        for entry in other {
+            self.merge_entry_with(entry, options);
+        }
+    }
+
+    /// Merges a single entry into the document, as described in
+    /// [`merge`](Self::merge).
+    pub fn merge_entry(&mut self, entry: Entry) {
+        self.merge_entry_with(entry, MergeOptions::default());
+    }
+
+    fn merge_entry_with(&mut self, entry: Entry, options: MergeOptions) {
+        self.raw = None;
+
+        if entry.key.is_empty() || entry.key == "/" {
+            self.entries.push(entry);
+            return;
+        }
+
+        let Some(existing) = self.get_mut(&entry.key) else {
+            self.entries.push(entry);
+            return;
+        };
+
+        match (&mut existing.value, entry.value) {
+            (Value::Block(existing_block), Value::Block(block)) => {
+                existing_block.merge_with(block, options);
+            }
+            (_, value) => {
+                existing.value = value;
+                existing.origin = entry.origin;
+            }
+        }
+    }
+
+    /// Substitutes the variables in the values of the document.
+    ///
+    /// `${NAME}` is replaced by the value of the variable `NAME` and
+    /// `${NAME:-default}` by `default` when the variable is not defined or
+    /// empty. `$${` is replaced by `${` without substituting a variable.
+    /// Errors are located at the `$` of the variable that could not be
+    /// substituted.
+    ///
+    /// ```
+    /// use serde_ccl::Document;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut document =
+    ///     Document::parse("url = http://${HOST}:${PORT:-80}/\nliteral = $${HOST}\n").unwrap();
+    ///
+    /// document.interpolate(&BTreeMap::from([("HOST", "localhost")])).unwrap();
+    /// assert_eq!(
+    ///     document.to_string(),
+    ///     "url = http://localhost:80/\nliteral = ${HOST}\n",
+    /// );
+    /// ```
+    pub fn interpolate<L>(&mut self, lookup: &L) -> Result<()>
+    where
+        L: Lookup + ?Sized,
+    {
+        interpolate_document(self, lookup)
+    }
+
+    /// Substitutes references to other entries of the document and variables
+    /// in the values of the document.
+    ///
+    /// `${a.b}` is replaced by the value of the entry at the [`KeyPath`] `a.b`,
+    /// after substituting the references in that value, so list items are
+    /// referred to as in `${ports[0]}` or `${ports.0}`. Names that are not the
+    /// path of an entry with a text value are looked up as variables, as in
+    /// [`interpolate`](Self::interpolate). References that form a cycle are an
+    /// error.
+    ///
+    /// ```
+    /// use serde_ccl::Document;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut document = Document::parse(
+    ///     "server =\n  host = ${HOST}\n  port = 8080\nurl = http://${server.host}:${server.port}\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// document.resolve_references(&BTreeMap::from([("HOST", "example.com")])).unwrap();
+    /// assert_eq!(
+    ///     document.get("url").unwrap().value().as_text(),
+    ///     Some("http://example.com:8080"),
+    /// );
+    /// ```
+    pub fn resolve_references<L>(&mut self, lookup: &L) -> Result<()>
+    where
+        L: Lookup + ?Sized,
+    {
+        resolve_document_references(self, lookup)
+    }
+
+    /// Sets the value at the given path, creating the missing entries.
+    ///
+    /// Entries are found as in [`Document::get_path`], so keys that are
+    /// numbers refer to list items unless an entry has them as its key. Text
+    /// values along the path are replaced by blocks. Indexing a list item that
+    /// does not exist is an error.
+    pub fn set_path<V>(&mut self, path: &KeyPath, value: V) -> Result<&mut Entry>
+    where
+        V: Into<Value>,
+    {
+        set_path(self, path, value.into())
+    }
+
+    /// Returns the entry at the given path.
+    ///
+    /// Keys refer to the last entry with that key. Keys that are numbers and
+    /// that no entry has refer to list items, so `ports.1` is the same as
+    /// `ports[1]`.
+    ///
+    /// ```
+    /// use serde_ccl::{Document, KeyPath};
+    ///
+    /// let document = Document::parse("database =\n  ports =\n    = 5432\n    = 5433\n").unwrap();
+    /// let path = "database.ports.1".parse::<KeyPath>().unwrap();
+    ///
+    /// let entry = document.get_path(&path).unwrap();
+    /// assert_eq!(entry.value().as_text(), Some("5433"));
+    /// ```
+    #[must_use]
+    pub fn get_path(&self, path: &KeyPath) -> Option<&Entry> {
+        get_all_path(self, path).pop()
+    }
+
+    /// Returns the entries at the given path.
+    ///
+    /// If the last segment of the path is a key, all entries with that key are
+    /// returned, in the order they appear in.
+    #[must_use]
+    pub fn get_all_path(&self, path: &KeyPath) -> Vec<&Entry> {
+        get_all_path(self, path)
+    }
+
+    /// Returns the last entry with the given key.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&Entry> {
+        self.entries.iter().rev().find(|entry| entry.key == key)
+    }
+
+    /// Returns a mutable reference to the last entry with the given key.
+    #[must_use]
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Entry> {
+        self.raw = None;
+        self.entries.iter_mut().rev().find(|entry| entry.key == key)
+    }
+
+    /// Returns an iterator over the entries with the given key.
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a Entry> + 'a {
+        self.entries.iter().filter(move |entry| entry.key == key)
+    }
+
+    #[must_use]
+    pub(crate) fn render(&self) -> Rendered<'_> {
+        let mut rendered = Rendered {
+            text: String::new(),
+            mappings: Vec::new(),
+        };
+
+        render_entries(
+            &self.entries,
+            0,
+            &mut rendered.text,
+            &mut Some(&mut rendered.mappings),
+        );
+        rendered
+    }
+}
+
+impl FromIterator<Entry> for Document {
+    fn from_iter<I>(entries: I) -> Self
+    where
+        I: IntoIterator<Item = Entry>,
+    {
+        Self {
+            entries: entries.into_iter().collect(),
+            raw: None,
+        }
+    }
+}
+
+impl PartialEq for Document {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl Eq for Document {
+    // Empty
+}
+
+impl core::hash::Hash for Document {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: core::hash::Hasher,
+    {
+        self.entries.hash(state);
+    }
+}
+
+impl fmt::Debug for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Document")
+            .field("entries", &self.entries)
+            .finish_non_exhaustive()
+    }
+}
+
+impl IntoIterator for Document {
+    type Item = Entry;
+    type IntoIter = alloc::vec::IntoIter<Entry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Document {
+    type Item = &'a Entry;
+    type IntoIter = core::slice::Iter<'a, Entry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut text = String::new();
+        render_entries(&self.entries, 0, &mut text, &mut None);
+        f.write_str(&text)
+    }
+}
+
+/// Deserializes the value from a parsed document.
+///
+/// Errors are located in the inputs the entries of the document were parsed
+/// from.
+pub fn from_document<T>(document: &Document) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let rendered = document.render();
+    crate::from_str(&rendered.text).map_err(|e| rendered.locate_error(e))
+}
+
+/// Deserializes the value of an entry.
+///
+/// Errors are located in the input the entry was parsed from.
+pub(crate) fn from_entry_value<T>(entry: &Entry) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let (text, span) = match &entry.value {
+        Value::Text(text) => (text.as_str(), entry.origin.value_span),
+        Value::Block(Document { raw: Some(raw), .. }) => (raw.text(), raw.span),
+        Value::Block(block) => return from_document(block),
+    };
+
+    let rendered = Rendered {
+        text: text.into(),
+        mappings: vec![Mapping {
+            rendered_start: 0,
+            span,
+            origin: &entry.origin,
+        }],
+    };
+
+    crate::from_str(&rendered.text).map_err(|e| rendered.locate_error(e))
+}
+
+/// Line without an equal sign, found while parsing a document.
+pub(crate) struct MissingEq {
+    /// Range of the text of the line.
+    pub range: Range<usize>,
+
+    /// Line of the key the line is part of, if any.
+    pub key_line: Option<usize>,
+}
+
+struct DocumentParser<'a> {
+    text: &'a str,
+    input: Option<Arc<str>>,
+    line_index: &'a LineIndex<'a>,
+    source: &'a Source,
+    recover: bool,
+    missing_eqs: Vec<MissingEq>,
+}
+
+impl<'a> DocumentParser<'a> {
+    #[must_use]
+    fn new(
+        text: &'a str,
+        line_index: &'a LineIndex<'a>,
+        source: &'a Source,
+        recover: bool,
+    ) -> Self {
+        Self {
+            text,
+            input: None,
+            line_index,
+            source,
+            recover,
+            missing_eqs: Vec::new(),
+        }
+    }
+
+    /// Parses the entries between the given offsets.
+    ///
+    /// Lines that are part of a key spanning multiple lines are recorded, as
+    /// are the lines without an equal sign at the end of the block, unless
+    /// they are the whole value of a nested block. When recovering, they are
+    /// skipped at the top level and after other entries of nested blocks, and
+    /// they are an error otherwise.
+    fn parse_block(&mut self, start: usize, end: usize, nested: bool) -> Result<Document> {
+        let data = &self.text.as_bytes()[start..end];
+        let mut parser = SliceParser::new(data);
+        let mut entries = Vec::new();
+
+        loop {
+            if let IndentState::Eof = parser.skip_whitespace_raw() {
+                break;
+            }
+
+            let rest_start = start + parser.index();
+
+            let key = match parser.parse_key_raw() {
+                Ok(key) => key,
+                Err(e) => {
+                    let recoverable = !nested || !entries.is_empty();
+
+                    if recoverable {
+                        self.record_missing_eqs(rest_start, end, None);
+                    }
+
+                    if self.recover && recoverable {
+                        break;
+                    }
+
+                    let location = self.location(start + e.index());
+                    return Err(e.with_location(location));
+                }
+            };
+
+            let mut key_start = start + parser.last_key_index();
+            let key_end = start + parser.last_key_end();
+
+            if let Some(newline) = memchr::memrchr(b'\n', key) {
+                let last_line_start = key_start + newline + 1;
+                let last_line = self.slice(last_line_start, key_end);
+                let last_key_start = key_end - last_line.trim_start_matches(' ').len();
+
+                let key_line = self.line_index.line(last_key_start);
+                self.record_missing_eqs(key_start, last_line_start, Some(key_line));
+
+                if !(self.recover && (!nested || !entries.is_empty())) {
+                    return Err(Error::new(ErrorCode::ExpectedEq, self.location(key_start)));
+                }
+
+                key_start = last_key_start;
+            }
+
+            let key_span = Span::new(self.location(key_start), key_end);
+
+            let value_search_start = parser.index();
+            let value = parser.parse_value_raw();
+
+            let value_start = if value.is_empty() {
+                start + value_search_start
+            } else {
+                start + unsafe { parser.index_of_ptr(value.as_ptr()) }
+            };
+
+            let value_end = value_start + value.len();
+            let value_span = Span::new(self.location(value_start), value_end);
+
+            let starts_on_new_line =
+                memchr::memchr(b'\n', &data[value_search_start..value_start - start]).is_some();
+
+            let value = if value.is_empty() || !starts_on_new_line {
+                Value::Text(self.slice(value_start, value_end).into())
+            } else {
+                let block_start = self.text.as_bytes()[..value_start]
+                    .iter()
+                    .rposition(|&byte| byte != b' ')
+                    .map_or(0, |index| index + 1);
+
+                match self.parse_block(block_start, value_end, true) {
+                    Ok(mut block) => {
+                        block.raw = Some(self.raw(block_start, value_end));
+                        Value::Block(block)
+                    }
+                    Err(_) => Value::Text(self.slice(value_start, value_end).into()),
+                }
+            };
+
+            entries.push(Entry {
+                key: self.slice(key_start, key_end).into(),
+                value,
+                origin: Origin {
+                    source: self.source.clone(),
+                    key_span,
+                    value_span,
+                },
+            });
+        }
+
+        Ok(Document { entries, raw: None })
+    }
+
+    /// Records the lines between the given offsets that are not blank.
+    fn record_missing_eqs(&mut self, start: usize, end: usize, key_line: Option<usize>) {
+        let mut line_start = start;
+
+        for line in self.text[start..end].split('\n') {
+            let content = line.trim_end_matches([' ', '\r']);
+            let content_start =
+                line_start + (content.len() - content.trim_start_matches(' ').len());
+            let content_end = line_start + content.len();
+
+            if content_start < content_end {
+                self.missing_eqs.push(MissingEq {
+                    range: content_start..content_end,
+                    key_line,
+                });
+            }
+
+            line_start += line.len() + 1;
+        }
+    }
+
+    #[must_use]
+    fn raw(&mut self, start: usize, end: usize) -> Raw {
+        let input = self.input.get_or_insert_with(|| self.text.into()).clone();
+
+        Raw {
+            input,
+            span: Span::new(self.location(start), end),
+        }
+    }
+
+    #[must_use]
+    fn location(&self, index: usize) -> Location {
+        self.line_index.location(index)
+    }
+
+    #[must_use]
+    fn slice(&self, start: usize, end: usize) -> &'a str {
+        &self.text[start..end]
+    }
+}
+
+/// Document rendered as CCL, together with the origins of the rendered keys
+/// and values.
+pub(crate) struct Rendered<'a> {
+    pub text: String,
+    mappings: Vec<Mapping<'a>>,
+}
+
+struct Mapping<'a> {
+    rendered_start: usize,
+    span: Span,
+    origin: &'a Origin,
+}
+
+impl Rendered<'_> {
+    /// Maps the location of an error in the rendered text to the input the
+    /// entry at that location was parsed from.
+    #[must_use]
+    pub fn locate_error(&self, error: Error) -> Error {
+        if error.location().is_default() {
+            return error;
+        }
+
+        let index = error.index();
+        let mapping_index = self
+            .mappings
+            .partition_point(|mapping| mapping.rendered_start <= index);
+
+        let Some(mapping) = mapping_index
+            .checked_sub(1)
+            .map(|mapping_index| &self.mappings[mapping_index])
+        else {
+            return error;
+        };
+
+        if !mapping.origin.is_parsed() {
+            return error
+                .with_location(Location::default())
+                .with_input(mapping.origin.source.clone());
+        }
+
+        let prefix = &self.text[mapping.rendered_start..index];
+        let location = mapping.span.location().advance(prefix);
+
+        error
+            .with_location(location)
+            .with_input(mapping.origin.source.clone())
+    }
+}
+
+fn render_entries<'a>(
+    entries: &'a [Entry],
+    indent: usize,
+    text: &mut String,
+    mappings: &mut Option<&mut Vec<Mapping<'a>>>,
+) {
+    for entry in entries {
+        push_indent(text, indent);
+
+        if let Some(mappings) = mappings {
+            mappings.push(Mapping {
+                rendered_start: text.len(),
+                span: entry.origin.key_span,
+                origin: &entry.origin,
+            });
+        }
+
+        match entry.key.as_str() {
+            "" => text.push('='),
+            "/" => text.push_str("/="),
+            key => {
+                text.push_str(key);
+                text.push_str(" =");
+            }
+        }
+
+        match &entry.value {
+            Value::Text(value) if !value.is_empty() => {
+                text.push(' ');
+
+                if let Some(mappings) = mappings {
+                    mappings.push(Mapping {
+                        rendered_start: text.len(),
+                        span: entry.origin.value_span,
+                        origin: &entry.origin,
+                    });
+                }
+
+                push_text(text, value, indent);
+                text.push('\n');
+            }
+            Value::Block(Document { raw: Some(raw), .. }) => {
+                text.push('\n');
+
+                if let Some(mappings) = mappings {
+                    mappings.push(Mapping {
+                        rendered_start: text.len(),
+                        span: raw.span,
+                        origin: &entry.origin,
+                    });
+                }
+
+                push_lines(text, raw.text(), indent);
+                text.push('\n');
+            }
+            Value::Block(block) if !block.is_empty() => {
+                text.push('\n');
+                render_entries(&block.entries, indent + INDENT, text, mappings);
+            }
+            _ => text.push('\n'),
+        }
+    }
+}
+
+pub(crate) fn push_indent(text: &mut String, indent: usize) {
+    text.extend(core::iter::repeat_n(' ', indent));
+}
+
+/// Writes text spanning multiple lines, indenting the continuation lines
+/// deeper than the key when they are not already.
+pub(crate) fn push_text(text: &mut String, value: &str, indent: usize) {
+    let mut lines = value.split('\n');
+    text.push_str(lines.next().unwrap_or_default());
+
+    let min_indent = lines
+        .clone()
+        .filter(|line| !line.trim_start_matches(' ').is_empty())
+        .map(|line| line.len() - line.trim_start_matches(' ').len())
+        .min();
+
+    let extra_indent = match min_indent {
+        Some(min_indent) if min_indent <= indent => indent + INDENT - min_indent,
+        _ => 0,
+    };
+
+    for line in lines {
+        text.push('\n');
+
+        if !line.is_empty() {
+            push_indent(text, extra_indent);
+        }
+
+        text.push_str(line);
+    }
+}
+
+/// Writes lines that follow a key at the given indentation as they are,
+/// unless they are not indented deeper than the key, in which case they are
+/// all indented further.
+fn push_lines(text: &mut String, lines: &str, indent: usize) {
+    let min_indent = lines
+        .split('\n')
+        .filter(|line| !line.trim_start_matches(' ').is_empty())
+        .map(|line| line.len() - line.trim_start_matches(' ').len())
+        .min()
+        .unwrap_or_default();
+
+    let extra_indent = if min_indent <= indent {
+        indent + INDENT - min_indent
+    } else {
+        0
+    };
+
+    for (i, line) in lines.split('\n').enumerate() {
+        if i != 0 {
+            text.push('\n');
+        }
+
+        if !line.is_empty() {
+            push_indent(text, extra_indent);
+        }
+
+        text.push_str(line);
+    }
+}
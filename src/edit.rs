@@ -0,0 +1,307 @@
+use crate::document::{push_indent, push_text, Document, Entry, Source, Value, INDENT};
+use crate::error::{Error, ErrorCode, Result};
+use crate::path::{find_entry, missing_item, KeyPath, Segment};
+use crate::position::Location;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// Edits the text of a CCL document in place, keeping everything but the
+/// edited entries byte for byte, including comments, blank lines and
+/// indentation.
+///
+/// Entries are found with the same [`KeyPath`] syntax as
+/// [`Document::get_path`]. New entries are added after the last entry of
+/// their block, indented like its other entries.
+///
+/// ```
+/// use serde_ccl::{Editor, KeyPath};
+///
+/// let mut editor = Editor::parse("/= Database\ndatabase =\n  port = 5432\n  /= TODO\n").unwrap();
+///
+/// editor.set(&"database.port".parse().unwrap(), "6543").unwrap();
+/// editor.set(&"database.host".parse().unwrap(), "localhost").unwrap();
+/// editor.set(&"log.level".parse().unwrap(), "debug").unwrap();
+///
+/// assert_eq!(
+///     editor.text(),
+///     "/= Database\ndatabase =\n  port = 6543\n  /= TODO\n  host = localhost\nlog =\n  level = debug\n",
+/// );
+///
+/// assert!(editor.unset(&"database.port".parse().unwrap()).unwrap());
+/// assert_eq!(
+///     editor.text(),
+///     "/= Database\ndatabase =\n  /= TODO\n  host = localhost\nlog =\n  level = debug\n",
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Editor {
+    text: String,
+    source: Source,
+    document: Document,
+}
+
+impl Editor {
+    /// Parses the document to edit.
+    pub fn parse(text: &str) -> Result<Self> {
+        Self::parse_with_source(text, Source::Anonymous)
+    }
+
+    /// Parses the document to edit, recording the given source in errors.
+    pub fn parse_with_source(text: &str, source: Source) -> Result<Self> {
+        let document = Document::parse_with_source(text, source.clone())?;
+
+        Ok(Self {
+            text: text.into(),
+            source,
+            document,
+        })
+    }
+
+    /// Returns the edited text.
+    #[inline]
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the edited document.
+    #[inline]
+    #[must_use]
+    pub fn document(&self) -> &Document {
+        &self.document
+    }
+
+    /// Returns the edited text.
+    #[inline]
+    #[must_use]
+    pub fn into_text(self) -> String {
+        self.text
+    }
+
+    /// Sets the value at the given path, adding the missing entries.
+    ///
+    /// Nested entries and text along the path are replaced by the new value.
+    /// Indexing a list item that does not exist is an error.
+    pub fn set(&mut self, path: &KeyPath, value: &str) -> Result<()> {
+        let value = value.trim_matches([' ', '\n']);
+        let (range, text) = self.set_edit(path, value).map_err(|e| self.locate(e))?;
+        self.apply([(range, text)])
+    }
+
+    /// Removes the entries at the given path, returning whether there were
+    /// any.
+    ///
+    /// If the last segment of the path is a key, all entries with that key are
+    /// removed, as found by [`Document::get_all_path`].
+    pub fn unset(&mut self, path: &KeyPath) -> Result<bool> {
+        let mut edits = self
+            .document
+            .get_all_path(path)
+            .into_iter()
+            .map(|entry| (self.entry_lines(entry), String::new()))
+            .collect::<Vec<_>>();
+
+        if edits.is_empty() {
+            return Ok(false);
+        }
+
+        edits.reverse();
+        self.apply(edits)?;
+        Ok(true)
+    }
+
+    fn set_edit(&self, path: &KeyPath, value: &str) -> Result<(Range<usize>, String)> {
+        let segments = path.segments();
+        let unit = indent_unit(&self.document).unwrap_or(INDENT);
+
+        let mut block = &self.document;
+        let mut parent = None;
+
+        for (i, segment) in segments.iter().enumerate() {
+            let Some(entry) = find_entry(block, segment) else {
+                if let Some(index) = missing_item(block, segment) {
+                    return Err(Error::new(
+                        ErrorCode::IndexOutOfBounds(index),
+                        Location::default(),
+                    ));
+                }
+
+                return self.insert_edit(parent, block, &segments[i..], value, unit);
+            };
+
+            let rest = &segments[i + 1..];
+
+            match entry.value() {
+                Value::Block(entry_block) if !rest.is_empty() => {
+                    parent = Some(entry);
+                    block = entry_block;
+                }
+                _ => return self.replace_edit(entry, rest, value, unit),
+            }
+        }
+
+        Err(Error::new(ErrorCode::InvalidPath, Location::default()))
+    }
+
+    /// Replaces the value of the entry with the value at the rest of the path.
+    fn replace_edit(
+        &self,
+        entry: &Entry,
+        rest: &[Segment],
+        value: &str,
+        unit: usize,
+    ) -> Result<(Range<usize>, String)> {
+        let key_end = entry.origin().key_span().end();
+        let eq_end = key_end + self.text[key_end..].find('=').unwrap_or_default() + 1;
+        let end = entry.origin().value_span().end().max(eq_end);
+        let indent = indent_of(entry);
+
+        let mut text = String::new();
+
+        if rest.is_empty() {
+            push_value(&mut text, value, indent);
+        } else {
+            text.push('\n');
+            push_entries(&mut text, rest, value, indent + unit, unit)?;
+        }
+
+        Ok((eq_end..end, text))
+    }
+
+    /// Adds the entries at the rest of the path after the last entry of the
+    /// block.
+    fn insert_edit(
+        &self,
+        parent: Option<&Entry>,
+        block: &Document,
+        rest: &[Segment],
+        value: &str,
+        unit: usize,
+    ) -> Result<(Range<usize>, String)> {
+        let indent = block.entries().first().map_or(0, indent_of);
+        let end = match (parent, block.entries().last()) {
+            (Some(parent), _) => Some(parent.origin().end()),
+            (None, Some(last)) => Some(last.origin().end()),
+            (None, None) => None,
+        };
+
+        let mut text = String::new();
+
+        let Some(end) = end else {
+            if !(self.text.is_empty() || self.text.ends_with('\n')) {
+                text.push('\n');
+            }
+
+            push_entries(&mut text, rest, value, indent, unit)?;
+            text.push('\n');
+            return Ok((self.text.len()..self.text.len(), text));
+        };
+
+        text.push('\n');
+        push_entries(&mut text, rest, value, indent, unit)?;
+        Ok((end..end, text))
+    }
+
+    /// Returns the range of the lines of the entry, including the newline
+    /// that ends them.
+    fn entry_lines(&self, entry: &Entry) -> Range<usize> {
+        let start = entry.origin().key_span().start();
+        let start = self.text[..start].rfind('\n').map_or(0, |index| index + 1);
+
+        let end = entry.origin().end();
+        let end = self.text[end..]
+            .find('\n')
+            .map_or(self.text.len(), |index| end + index + 1);
+
+        start..end
+    }
+
+    /// Applies edits ordered from last to first and parses the edited text.
+    fn apply<I>(&mut self, edits: I) -> Result<()>
+    where
+        I: IntoIterator<Item = (Range<usize>, String)>,
+    {
+        let mut text = self.text.clone();
+
+        for (range, replacement) in edits {
+            text.replace_range(range, &replacement);
+        }
+
+        self.document = Document::parse_with_source(&text, self.source.clone())?;
+        self.text = text;
+        Ok(())
+    }
+
+    fn locate(&self, error: Error) -> Error {
+        error.with_input(self.source.clone())
+    }
+}
+
+/// Writes the entries at the path, with the value at the last entry.
+fn push_entries(
+    text: &mut String,
+    segments: &[Segment],
+    value: &str,
+    indent: usize,
+    unit: usize,
+) -> Result<()> {
+    let Some((segment, rest)) = segments.split_first() else {
+        return Ok(());
+    };
+
+    push_indent(text, indent);
+
+    match segment {
+        Segment::Key(key) => {
+            if key.contains(['=', '\n']) || key.trim_matches(' ') != key {
+                return Err(Error::new(
+                    ErrorCode::InvalidKey(key.clone()),
+                    Location::default(),
+                ));
+            }
+
+            text.push_str(key);
+            text.push_str(" =");
+        }
+        Segment::Index(index) => {
+            return Err(Error::new(
+                ErrorCode::IndexOutOfBounds(*index),
+                Location::default(),
+            ));
+        }
+        Segment::Append => text.push('='),
+    }
+
+    if rest.is_empty() {
+        push_value(text, value, indent);
+        return Ok(());
+    }
+
+    text.push('\n');
+    push_entries(text, rest, value, indent + unit, unit)
+}
+
+fn push_value(text: &mut String, value: &str, indent: usize) {
+    if !value.is_empty() {
+        text.push(' ');
+        push_text(text, value, indent);
+    }
+}
+
+/// Returns the indentation of the key of the entry.
+#[must_use]
+fn indent_of(entry: &Entry) -> usize {
+    entry.origin().key_span().column() - 1
+}
+
+/// Returns how much deeper the entries of the first nested block are indented
+/// than their key.
+#[must_use]
+fn indent_unit(document: &Document) -> Option<usize> {
+    document.iter().find_map(|entry| {
+        let block = entry.value().as_block()?;
+        let child = block.entries().first()?;
+        indent_of(child).checked_sub(indent_of(entry))
+    })
+}
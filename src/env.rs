@@ -0,0 +1,211 @@
+use crate::document::{Document, Entry, Origin, Source};
+use crate::spanned::Span;
+use std::collections::BTreeMap;
+use std::env;
+
+/// Default separator between the prefix and the keys of environment
+/// variables.
+pub const DEFAULT_ENV_SEPARATOR: &str = "__";
+
+/// Builds a document from environment variables, to be merged over the
+/// documents read from files.
+///
+/// A variable such as `APP__DATABASE__PORT=5432` with the prefix `APP` sets
+/// the key path `database.port` to `5432`. Keys are lowercased unless
+/// configured otherwise. Numeric keys are list items, ordered by their index,
+/// so `APP__HOSTS__0` and `APP__HOSTS__1` are appended to the `hosts` list
+/// when [merged](crate::Document::merge).
+///
+/// Errors in values from environment variables name the variable instead of a
+/// line and column.
+///
+/// ```
+/// use serde::Deserialize;
+/// use serde_ccl::{Document, EnvOverrides, Layers};
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     database: Database,
+///     hosts: Vec<String>,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Database {
+///     port: u16,
+/// }
+///
+/// let file = Document::parse("database =\n  port = 3306\nhosts =\n  = a.com\n").unwrap();
+/// let env = EnvOverrides::new("APP").load_from([
+///     ("APP__DATABASE__PORT", "5432"),
+///     ("APP__HOSTS__0", "b.com"),
+///     ("OTHER", "ignored"),
+/// ]);
+///
+/// let config = Layers::new()
+///     .with_layer("file", file)
+///     .with_layer("environment", env)
+///     .deserialize::<Config>()
+///     .unwrap();
+///
+/// assert_eq!(config.database.port, 5432);
+/// assert_eq!(config.hosts, ["a.com", "b.com"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct EnvOverrides {
+    prefix: String,
+    separator: String,
+    lowercase_keys: bool,
+}
+
+impl EnvOverrides {
+    /// Creates overrides from the variables that start with the given prefix
+    /// followed by the separator.
+    #[must_use]
+    pub fn new<P>(prefix: P) -> Self
+    where
+        P: Into<String>,
+    {
+        Self {
+            prefix: prefix.into(),
+            separator: DEFAULT_ENV_SEPARATOR.into(),
+            lowercase_keys: true,
+        }
+    }
+
+    /// Sets the separator between the prefix and the keys of a variable.
+    #[must_use]
+    pub fn with_separator<S>(mut self, separator: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Sets whether keys are lowercased, which is the default.
+    #[must_use]
+    pub fn with_lowercase_keys(mut self, lowercase_keys: bool) -> Self {
+        self.lowercase_keys = lowercase_keys;
+        self
+    }
+
+    /// Builds a document from the variables of the current process.
+    ///
+    /// Variables whose names or values are not valid UTF-8 are ignored.
+    #[must_use]
+    pub fn load(&self) -> Document {
+        self.load_from(env::vars_os().filter_map(|(name, value)| {
+            Some((name.into_string().ok()?, value.into_string().ok()?))
+        }))
+    }
+
+    /// Builds a document from the given variables.
+    #[must_use]
+    pub fn load_from<I, N, V>(&self, vars: I) -> Document
+    where
+        I: IntoIterator<Item = (N, V)>,
+        N: AsRef<str>,
+        V: Into<String>,
+    {
+        let mut vars = vars
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let keys = self.keys(name.as_ref())?;
+                Some((name.as_ref().to_owned(), keys, value.into()))
+            })
+            .collect::<Vec<_>>();
+
+        vars.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+
+        let mut root = BTreeMap::new();
+
+        for (name, keys, value) in vars {
+            let origin = Origin::new(Source::environment(&name), Span::default(), Span::default());
+
+            insert(&mut root, &keys, value, origin);
+        }
+
+        into_document(root)
+    }
+
+    fn keys(&self, name: &str) -> Option<Vec<Key>> {
+        let rest = if self.prefix.is_empty() {
+            name
+        } else {
+            name.strip_prefix(self.prefix.as_str())?
+                .strip_prefix(self.separator.as_str())?
+        };
+
+        rest.split(self.separator.as_str())
+            .map(|key| match key {
+                "" => None,
+                key => Some(match key.parse::<usize>() {
+                    Ok(index) => Key::Index(index),
+                    Err(_) if self.lowercase_keys => Key::Name(key.to_lowercase()),
+                    Err(_) => Key::Name(key.into()),
+                }),
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Key {
+    Index(usize),
+    Name(String),
+}
+
+#[derive(Debug)]
+enum Node {
+    Text(String, Origin),
+    Block(BTreeMap<Key, Node>, Origin),
+}
+
+fn insert(block: &mut BTreeMap<Key, Node>, keys: &[Key], value: String, origin: Origin) {
+    let Some((key, keys)) = keys.split_first() else {
+        return;
+    };
+
+    if keys.is_empty() {
+        block.insert(key.clone(), Node::Text(value, origin));
+        return;
+    }
+
+    let node = block
+        .entry(key.clone())
+        .or_insert_with(|| Node::Block(BTreeMap::new(), origin.clone()));
+
+    if let Node::Text(..) = node {
+        *node = Node::Block(BTreeMap::new(), origin.clone());
+    }
+
+    if let Node::Block(block, _) = node {
+        insert(block, keys, value, origin);
+    }
+}
+
+/// Converts the nodes to entries, with named keys first and list items
+/// ordered by their index.
+fn into_document(block: BTreeMap<Key, Node>) -> Document {
+    let (items, named): (Vec<_>, Vec<_>) = block
+        .into_iter()
+        .partition(|(key, _)| matches!(key, Key::Index(_)));
+
+    named
+        .into_iter()
+        .chain(items)
+        .map(|(key, node)| {
+            let key = match key {
+                Key::Index(_) => String::new(),
+                Key::Name(name) => name,
+            };
+
+            match node {
+                Node::Text(value, origin) => Entry::new(key, value).with_origin(origin),
+                Node::Block(block, origin) => {
+                    Entry::new(key, into_document(block)).with_origin(origin)
+                }
+            }
+        })
+        .collect()
+}
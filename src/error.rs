@@ -1,9 +1,11 @@
-use crate::position::Position;
+use crate::document::Source;
+use crate::line_index::ColumnMode;
+use crate::position::{Location, Position};
 use alloc::boxed::Box;
 use alloc::string::{String, ToString};
 use core::error::Error as CoreError;
 use core::fmt;
-use serde_core::de;
+use serde_core::{de, ser};
 
 /// Result type returned by functions that can fail.
 pub type Result<T> = ::core::result::Result<T, Error>;
@@ -13,42 +15,104 @@ pub struct Error(Box<ErrorImpl>);
 
 pub(crate) struct ErrorImpl {
     code: ErrorCode,
-    position: Position,
+    location: Location,
+    input: Option<Source>,
 }
 
 pub(crate) enum ErrorCode {
     // Parser errors.
     ExpectedEq,
     InvalidUtf8,
+    InvalidPath,
+
+    // Include errors.
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+    #[cfg(feature = "std")]
+    IncludeCycle(String),
+    #[cfg(feature = "std")]
+    InvalidInclude,
+    #[cfg(feature = "std")]
+    IncludeNotAllowed(String),
+
+    // Interpolation errors.
+    UndefinedVariable(String),
+    UnresolvedReference(String),
+    ReferenceCycle(String),
+    InvalidInterpolation,
 
     // Semantic errors.
     Message(String),
+    IndexOutOfBounds(usize),
     InvalidBool,
     InvalidInt,
     InvalidFloat,
     InvalidChar,
+
+    // Serialization errors.
+    InvalidKey(String),
+    KeyMustBeScalar,
+    ExpectedBlock,
+
+    // Lint errors.
+    UnknownRule(String),
+
+    // Schema errors.
+    #[cfg(feature = "schema")]
+    InvalidSchema(String),
 }
 
 /// The kind of error.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
 pub enum ErrorKind {
     /// The input is not a valid CCL document.
     Syntax,
 
     /// The deserialized data is semantically incorrect.
     Semantic,
+
+    /// An input could not be read.
+    Io,
+
+    /// An included document could not be resolved.
+    Include,
+
+    /// A variable in a value could not be interpolated.
+    Interpolation,
+
+    /// A value given to the library, such as the name of a lint rule or a
+    /// schema, is invalid.
+    Argument,
 }
 
 impl Error {
     #[must_use]
-    pub(crate) fn new(code: ErrorCode, position: Position) -> Self {
-        Self(Box::new(ErrorImpl { code, position }))
+    pub(crate) fn new(code: ErrorCode, location: Location) -> Self {
+        Self(Box::new(ErrorImpl {
+            code,
+            location,
+            input: None,
+        }))
+    }
+
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub(crate) fn io(error: std::io::Error) -> Self {
+        Self::new(ErrorCode::Io(error), Location::default())
     }
 
     #[inline]
     #[must_use]
-    pub(crate) fn with_position(mut self, position: Position) -> Self {
-        self.0.position = position;
+    pub(crate) fn with_input(mut self, input: Source) -> Self {
+        self.0.input = Some(input);
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn with_location(mut self, location: Location) -> Self {
+        self.0.location = location;
         self
     }
 
@@ -61,22 +125,73 @@ impl Error {
 
     #[inline]
     #[must_use]
-    pub(crate) fn position(&self) -> Position {
-        self.0.position
+    pub(crate) fn location(&self) -> Location {
+        self.0.location
+    }
+
+    /// Returns the description of the error, without the input and location at
+    /// which it occurred.
+    #[must_use]
+    pub fn message(&self) -> String {
+        self.0.code.to_string()
     }
 
     /// Returns the line at which the error occurred.
     #[inline]
     #[must_use]
     pub fn line(&self) -> usize {
-        self.0.position.line
+        self.0.location.line
     }
 
-    /// Returns the column at which the error occurred.
+    /// Returns the column at which the error occurred, measured in bytes.
     #[inline]
     #[must_use]
     pub fn column(&self) -> usize {
-        self.0.position.column
+        self.0.location.column
+    }
+
+    /// Returns the column at which the error occurred, measured in Unicode
+    /// scalar values.
+    #[inline]
+    #[must_use]
+    pub fn column_chars(&self) -> usize {
+        self.0.location.column_chars
+    }
+
+    /// Returns the column at which the error occurred, measured in UTF-16 code
+    /// units.
+    #[inline]
+    #[must_use]
+    pub fn column_utf16(&self) -> usize {
+        self.0.location.column_utf16
+    }
+
+    /// Returns the position at which the error occurred, with the column
+    /// measured in the given mode.
+    #[inline]
+    #[must_use]
+    pub fn position(&self, mode: ColumnMode) -> Position {
+        Position {
+            line: self.0.location.line,
+            column: self.0.location.column(mode),
+        }
+    }
+
+    /// Returns the byte offset at which the error occurred.
+    #[inline]
+    #[must_use]
+    pub fn index(&self) -> usize {
+        self.0.location.index
+    }
+
+    /// Returns the input in which the error occurred, if known.
+    ///
+    /// The input is only known for errors that occur while parsing or
+    /// deserializing a [`Document`](crate::Document).
+    #[inline]
+    #[must_use]
+    pub fn input(&self) -> Option<&Source> {
+        self.0.input.as_ref()
     }
 }
 
@@ -85,35 +200,61 @@ impl de::Error for Error {
     where
         T: fmt::Display,
     {
-        Self(Box::new(ErrorImpl {
-            code: ErrorCode::Message(message.to_string()),
-            position: Position::default(),
-        }))
+        Self::new(ErrorCode::Message(message.to_string()), Location::default())
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T>(message: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Self::new(ErrorCode::Message(message.to_string()), Location::default())
     }
 }
 
 impl CoreError for Error {
-    // Empty
+    fn source(&self) -> Option<&(dyn CoreError + 'static)> {
+        match &self.0.code {
+            #[cfg(feature = "std")]
+            ErrorCode::Io(error) => Some(error),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Error({:?}", self.0.code.to_string())?;
+
+        if let Some(input) = &self.0.input {
+            write!(f, ", input: {:?}", input.to_string())?;
+        }
+
         write!(
             f,
-            "Error({:?}, line: {}, column: {})",
-            self.0.code.to_string(),
-            self.0.position.line,
-            self.0.position.column,
+            ", line: {}, column: {})",
+            self.0.location.line, self.0.location.column,
         )
     }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.code)?;
+
+        if let Some(input) = &self.0.input {
+            write!(f, " in {input}")?;
+        }
+
+        if self.0.location.is_default() {
+            return Ok(());
+        }
+
         write!(
             f,
-            "{} at line {} column {}",
-            self.0.code, self.0.position.line, self.0.position.column,
+            " at line {} column {}",
+            self.0.location.line, self.0.location.column,
         )
     }
 }
@@ -125,11 +266,31 @@ impl ErrorCode {
         match self {
             Self::Message(_) => ErrorKind::Syntax,
             Self::ExpectedEq => ErrorKind::Syntax,
+            Self::InvalidPath => ErrorKind::Syntax,
             Self::InvalidUtf8 => ErrorKind::Semantic,
+            #[cfg(feature = "std")]
+            Self::Io(_) => ErrorKind::Io,
+            #[cfg(feature = "std")]
+            Self::IncludeCycle(_) => ErrorKind::Include,
+            #[cfg(feature = "std")]
+            Self::InvalidInclude => ErrorKind::Include,
+            #[cfg(feature = "std")]
+            Self::IncludeNotAllowed(_) => ErrorKind::Include,
+            Self::UndefinedVariable(_) => ErrorKind::Interpolation,
+            Self::UnresolvedReference(_) => ErrorKind::Interpolation,
+            Self::ReferenceCycle(_) => ErrorKind::Interpolation,
+            Self::InvalidInterpolation => ErrorKind::Interpolation,
+            Self::IndexOutOfBounds(_) => ErrorKind::Semantic,
             Self::InvalidBool => ErrorKind::Semantic,
             Self::InvalidInt => ErrorKind::Semantic,
             Self::InvalidFloat => ErrorKind::Semantic,
             Self::InvalidChar => ErrorKind::Semantic,
+            Self::InvalidKey(_) => ErrorKind::Semantic,
+            Self::KeyMustBeScalar => ErrorKind::Semantic,
+            Self::ExpectedBlock => ErrorKind::Semantic,
+            Self::UnknownRule(_) => ErrorKind::Argument,
+            #[cfg(feature = "schema")]
+            Self::InvalidSchema(_) => ErrorKind::Argument,
         }
     }
 }
@@ -140,10 +301,32 @@ impl fmt::Display for ErrorCode {
             ErrorCode::Message(message) => f.write_str(message),
             ErrorCode::ExpectedEq => f.write_str("expected equal sign"),
             ErrorCode::InvalidUtf8 => f.write_str("invalid UTF-8"),
+            ErrorCode::InvalidPath => f.write_str("invalid key path"),
+            ErrorCode::IndexOutOfBounds(index) => write!(f, "index {index} out of bounds"),
+            #[cfg(feature = "std")]
+            ErrorCode::Io(error) => fmt::Display::fmt(error, f),
+            #[cfg(feature = "std")]
+            ErrorCode::IncludeCycle(path) => write!(f, "include cycle through {path}"),
+            #[cfg(feature = "std")]
+            ErrorCode::InvalidInclude => f.write_str("invalid include"),
+            #[cfg(feature = "std")]
+            ErrorCode::IncludeNotAllowed(path) => {
+                write!(f, "include of {path} outside of the allowed roots")
+            }
+            ErrorCode::UndefinedVariable(name) => write!(f, "undefined variable {name}"),
+            ErrorCode::UnresolvedReference(path) => write!(f, "unresolved reference {path}"),
+            ErrorCode::ReferenceCycle(path) => write!(f, "reference cycle through {path}"),
+            ErrorCode::InvalidInterpolation => f.write_str("invalid interpolation"),
             ErrorCode::InvalidBool => f.write_str("invalid bool"),
             ErrorCode::InvalidInt => f.write_str("invalid int"),
             ErrorCode::InvalidFloat => f.write_str("invalid float"),
             ErrorCode::InvalidChar => f.write_str("invalid char"),
+            ErrorCode::InvalidKey(key) => write!(f, "invalid key `{key}`"),
+            ErrorCode::KeyMustBeScalar => f.write_str("key must be a scalar"),
+            ErrorCode::ExpectedBlock => f.write_str("expected a map or a sequence"),
+            ErrorCode::UnknownRule(name) => write!(f, "unknown lint rule `{name}`"),
+            #[cfg(feature = "schema")]
+            ErrorCode::InvalidSchema(message) => write!(f, "invalid schema: {message}"),
         }
     }
 }
@@ -0,0 +1,230 @@
+use crate::document::{Document, Entry, Value};
+use crate::error::Result;
+use crate::schema::resolve;
+use crate::ser::to_document;
+use schemars::JsonSchema;
+use serde_core::Serialize;
+use serde_json::Value as Json;
+
+/// Generates an example document from the default value of a type, with the
+/// documentation of the type and of its fields written as comments.
+///
+/// The documentation comes from the schema [`schemars`] generates for the type.
+/// Each field is preceded by one `/=` comment per line of its documentation, or
+/// of the documentation of its type if the field is not documented. List items
+/// and entries of maps are not documented, as comments in maps would be read
+/// back as entries. The documentation of the type itself is written at the
+/// start of the document, and top-level entries are separated by blank lines.
+///
+/// ```
+/// use schemars::JsonSchema;
+/// use serde::Serialize;
+///
+/// /// Server settings.
+/// #[derive(Default, Serialize, JsonSchema)]
+/// struct Config {
+///     /// Address to listen on.
+///     host: String,
+///     /// Ports to listen on.
+///     ///
+///     /// Ports below 1024 need extra privileges.
+///     ports: Vec<u16>,
+///     limits: Limits,
+/// }
+///
+/// #[derive(Serialize, JsonSchema)]
+/// struct Limits {
+///     /// Maximum number of connections.
+///     connections: u32,
+/// }
+///
+/// impl Default for Limits {
+///     fn default() -> Self {
+///         Self { connections: 100 }
+///     }
+/// }
+///
+/// let example = serde_ccl::example::<Config>().unwrap();
+///
+/// assert_eq!(
+///     example,
+///     "\
+/// /= Server settings.
+///
+/// /= Address to listen on.
+/// host =
+///
+/// /= Ports to listen on.
+/// /=
+/// /= Ports below 1024 need extra privileges.
+/// ports =
+///
+/// limits =
+///     /= Maximum number of connections.
+///     connections = 100
+/// ",
+/// );
+/// ```
+pub fn example<T>() -> Result<String>
+where
+    T: Default + Serialize + JsonSchema,
+{
+    example_of(&T::default())
+}
+
+/// Generates an example document from a value, with the documentation of its
+/// type and of its fields written as comments, like [`example`].
+pub fn example_of<T>(value: &T) -> Result<String>
+where
+    T: ?Sized + Serialize + JsonSchema,
+{
+    let root = schemars::SchemaGenerator::default()
+        .into_root_schema_for::<T>()
+        .to_value();
+
+    let document = annotate(to_document(value)?, Some(&root), &root);
+
+    let mut sections = Vec::new();
+    let mut section = Document::default();
+
+    if let Some(description) = root.get("description").and_then(Json::as_str) {
+        let mut header = Document::default();
+        push_comments(&mut header, description);
+        sections.push(header);
+    }
+
+    for entry in document {
+        let is_comment = entry.key() == "/";
+        section.push(entry);
+
+        if !is_comment {
+            sections.push(core::mem::take(&mut section));
+        }
+    }
+
+    if !section.is_empty() {
+        sections.push(section);
+    }
+
+    let sections = sections.iter().map(ToString::to_string).collect::<Vec<_>>();
+    Ok(sections.join("\n"))
+}
+
+/// Adds the documentation of the fields before their entries.
+///
+/// Only fields are documented, as comments in maps would be read back as
+/// entries.
+fn annotate(document: Document, schema: Option<&Json>, root: &Json) -> Document {
+    let mut annotated = Document::default();
+
+    for entry in document {
+        let property = schema.and_then(|schema| property(schema, entry.key(), root));
+
+        if let Some(Property {
+            schema,
+            is_field: true,
+        }) = property
+        {
+            if let Some(description) = description(schema, root) {
+                push_comments(&mut annotated, description);
+            }
+        }
+
+        let (key, value) = entry.into_parts();
+
+        let value = match value {
+            Value::Block(block) => Value::Block(annotate(
+                block,
+                property.map(|property| property.schema),
+                root,
+            )),
+            text @ Value::Text(_) => text,
+        };
+
+        annotated.push(Entry::new(key, value));
+    }
+
+    annotated
+}
+
+fn push_comments(document: &mut Document, text: &str) {
+    for line in text.lines() {
+        document.push(Entry::new("/", line.trim_end()));
+    }
+}
+
+/// Schema of an entry.
+#[derive(Clone, Copy)]
+struct Property<'a> {
+    schema: &'a Json,
+
+    /// Whether the entry is a field, as opposed to a list item or an entry of
+    /// a map.
+    is_field: bool,
+}
+
+/// Returns the schema of the entry with the given key, or of list items if the
+/// key is empty.
+fn property<'a>(schema: &'a Json, key: &str, root: &'a Json) -> Option<Property<'a>> {
+    let schema = resolve_all(schema, root);
+
+    if key.is_empty() {
+        return schema.get("items").map(|schema| Property {
+            schema,
+            is_field: false,
+        });
+    }
+
+    if let Some(schema) = schema
+        .get("properties")
+        .and_then(|properties| properties.get(key))
+    {
+        return Some(Property {
+            schema,
+            is_field: true,
+        });
+    }
+
+    let alternatives = ["allOf", "anyOf", "oneOf"]
+        .into_iter()
+        .filter_map(|keyword| schema.get(keyword)?.as_array())
+        .flatten();
+
+    for alternative in alternatives {
+        if let Some(property) = property(alternative, key, root) {
+            return Some(property);
+        }
+    }
+
+    schema
+        .get("additionalProperties")
+        .filter(|schema| schema.is_object())
+        .map(|schema| Property {
+            schema,
+            is_field: false,
+        })
+}
+
+/// Returns the description of the schema, or of the schema it refers to.
+fn description<'a>(schema: &'a Json, root: &'a Json) -> Option<&'a str> {
+    schema
+        .get("description")
+        .or_else(|| resolve_all(schema, root).get("description"))
+        .and_then(Json::as_str)
+}
+
+/// Follows the `$ref`s of the schema.
+fn resolve_all<'a>(mut schema: &'a Json, root: &'a Json) -> &'a Json {
+    // Bound the number of references followed in case they form a cycle.
+    for _ in 0..32 {
+        match schema.get("$ref").and_then(Json::as_str) {
+            Some(reference) => match resolve(root, reference) {
+                Some(target) => schema = target,
+                None => break,
+            },
+            None => break,
+        }
+    }
+
+    schema
+}
@@ -0,0 +1,150 @@
+use crate::document::{push_indent, push_text, Document, Entry, Source, Value, INDENT};
+use crate::error::Result;
+use crate::line_index::LineIndex;
+use alloc::string::String;
+
+/// Formats a CCL document.
+///
+/// Nested entries are indented by four spaces, keys and values are separated
+/// by `" = "`, runs of blank lines between entries are collapsed into one and
+/// blank lines at the start and end of blocks are removed. Values spanning
+/// multiple lines keep their lines as written, unless their key is now
+/// indented as deep as they are, in which case they are indented further.
+///
+/// The document is parsed before formatting, so the formatted document has
+/// the same entries as the original one. Blocks that could not be formatted
+/// without changing the text of one of their values, and blocks with keys
+/// containing whitespace, which are more likely text that happens to contain
+/// `=`, such as a script, are kept as written.
+///
+/// ```
+/// let formatted = serde_ccl::format("name=app\n\n\nports =\n  =80\n  = 443  \n").unwrap();
+/// assert_eq!(formatted, "name = app\n\nports =\n    = 80\n    = 443\n");
+/// ```
+pub fn format(text: &str) -> Result<String> {
+    format_with_source(text, Source::Anonymous)
+}
+
+/// Formats a CCL document, recording the given source in parse errors.
+pub fn format_with_source(text: &str, source: Source) -> Result<String> {
+    let document = Document::parse_with_source(text, source)?;
+    let line_index = LineIndex::new(text);
+
+    let mut formatted = String::with_capacity(text.len());
+    format_entries(&document, 0, text, &line_index, &mut formatted);
+    Ok(formatted)
+}
+
+fn format_entries(
+    document: &Document,
+    indent: usize,
+    source: &str,
+    line_index: &LineIndex,
+    text: &mut String,
+) {
+    let mut last_line = None;
+
+    for entry in document {
+        let origin = entry.origin();
+
+        if last_line.is_some_and(|line| origin.key_span().line() > line + 1) {
+            text.push('\n');
+        }
+
+        last_line = Some(line_index.line(origin.value_span().end()));
+
+        push_indent(text, indent);
+
+        match entry.key() {
+            "" => text.push('='),
+            "/" => text.push_str("/="),
+            key => {
+                text.push_str(key);
+                text.push_str(" =");
+            }
+        }
+
+        match entry.value() {
+            Value::Text(value) if value.is_empty() => text.push('\n'),
+            Value::Text(value) if origin.value_span().line() > origin.key_span().line() => {
+                push_text_on_new_line(text, entry, value, indent);
+                text.push('\n');
+            }
+            Value::Text(value) => {
+                text.push(' ');
+                push_text(text, value, indent);
+                text.push('\n');
+            }
+            Value::Block(block) if !keeps_values(block, indent + INDENT) => {
+                let value = &source[origin.value_span().range()];
+                push_text_on_new_line(text, entry, value, indent);
+                text.push('\n');
+            }
+            Value::Block(block) => {
+                text.push('\n');
+                format_entries(block, indent + INDENT, source, line_index, text);
+            }
+        }
+    }
+}
+
+/// Returns whether the entries of the block can be formatted at the given
+/// indentation without changing their values or making them ambiguous.
+fn keeps_values(block: &Document, indent: usize) -> bool {
+    block.iter().all(|entry| {
+        if entry.key().contains(char::is_whitespace) {
+            return false;
+        }
+
+        match entry.value() {
+            Value::Text(value) => {
+                let first_indent = entry.origin().value_span().column() - 1;
+                let on_new_line =
+                    entry.origin().value_span().line() > entry.origin().key_span().line();
+
+                value
+                    .split('\n')
+                    .skip(1)
+                    .filter(|line| !line.trim_start_matches(' ').is_empty())
+                    .map(|line| line.len() - line.trim_start_matches(' ').len())
+                    .chain(on_new_line.then_some(first_indent))
+                    .all(|line_indent| line_indent > indent)
+            }
+            Value::Block(block) => keeps_values(block, indent + INDENT),
+        }
+    })
+}
+
+/// Writes text that starts on the line following its key, keeping the
+/// indentation of its first line.
+fn push_text_on_new_line(text: &mut String, entry: &Entry, value: &str, indent: usize) {
+    let first_indent = entry.origin().value_span().column() - 1;
+    let mut lines = value.split('\n');
+    let first_line = lines.next().unwrap_or_default();
+
+    let min_indent = lines
+        .clone()
+        .filter(|line| !line.trim_start_matches(' ').is_empty())
+        .map(|line| line.len() - line.trim_start_matches(' ').len())
+        .fold(first_indent, usize::min);
+
+    let extra_indent = if min_indent <= indent {
+        indent + INDENT - min_indent
+    } else {
+        0
+    };
+
+    text.push('\n');
+    push_indent(text, first_indent + extra_indent);
+    text.push_str(first_line);
+
+    for line in lines {
+        text.push('\n');
+
+        if !line.is_empty() {
+            push_indent(text, extra_indent);
+        }
+
+        text.push_str(line);
+    }
+}
@@ -0,0 +1,105 @@
+/// Returns whether the path contains glob metacharacters.
+#[must_use]
+pub(crate) fn is_pattern(path: &str) -> bool {
+    path.contains(['*', '?', '['])
+}
+
+/// Matches a path component against a pattern component.
+///
+/// Supports `*` for any sequence of characters, `?` for any character and
+/// `[...]` for a set of characters, which may contain ranges such as `a-z` and
+/// be negated with a leading `!`. Leading dots must be matched explicitly.
+#[must_use]
+pub(crate) fn matches(pattern: &str, name: &str) -> bool {
+    if name.starts_with('.') && !pattern.starts_with('.') {
+        return false;
+    }
+
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+
+    let mut p = 0;
+    let mut n = 0;
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+                continue;
+            }
+            Some('?') => {
+                p += 1;
+                n += 1;
+                continue;
+            }
+            Some('[') => {
+                if let Some((is_match, len)) = match_class(&pattern[p..], name[n]) {
+                    if is_match {
+                        p += len;
+                        n += 1;
+                        continue;
+                    }
+                } else if name[n] == '[' {
+                    p += 1;
+                    n += 1;
+                    continue;
+                }
+            }
+            Some(&c) if c == name[n] => {
+                p += 1;
+                n += 1;
+                continue;
+            }
+            _ => (),
+        }
+
+        let Some((star_p, star_n)) = backtrack else {
+            return false;
+        };
+
+        backtrack = Some((star_p, star_n + 1));
+        p = star_p + 1;
+        n = star_n + 1;
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Matches a character against a character class at the start of the
+/// pattern, returning whether it matched and the length of the class, or
+/// `None` if the class is not terminated.
+#[must_use]
+fn match_class(pattern: &[char], c: char) -> Option<(bool, usize)> {
+    let mut i = 1;
+    let negated = matches!(pattern.get(i), Some('!' | '^'));
+
+    if negated {
+        i += 1;
+    }
+
+    let mut is_match = false;
+    let mut first = true;
+
+    loop {
+        let start = *pattern.get(i)?;
+
+        if start == ']' && !first {
+            return Some((is_match != negated, i + 1));
+        }
+
+        first = false;
+
+        match (pattern.get(i + 1), pattern.get(i + 2)) {
+            (Some('-'), Some(&end)) if end != ']' => {
+                is_match |= (start..=end).contains(&c);
+                i += 3;
+            }
+            _ => {
+                is_match |= start == c;
+                i += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,562 @@
+mod glob;
+
+use crate::document::{Document, Entry, Source, Value};
+use crate::error::{Error, ErrorCode, Result};
+use crate::spanned::Span;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use std::{env, fs, io};
+
+/// Key of the entries that list the documents to include by default.
+pub const DEFAULT_INCLUDE_KEY: &str = "imports";
+
+/// Locates and reads the documents included by other documents.
+pub trait Resolver {
+    /// Resolves the path of an included document to an identifier unique to
+    /// that document.
+    ///
+    /// `parent` is the identifier of the including document, or `None` when
+    /// resolving the root document.
+    fn resolve(&self, path: &str, parent: Option<&str>) -> io::Result<String>;
+
+    /// Expands a glob pattern to the identifiers of the matching documents,
+    /// in lexicographic order.
+    ///
+    /// Patterns are resolved like paths passed to [`resolve`](Self::resolve).
+    /// The default implementation does not support patterns.
+    fn expand(&self, pattern: &str, parent: Option<&str>) -> io::Result<Vec<String>> {
+        let _ = (pattern, parent);
+
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "glob patterns are not supported",
+        ))
+    }
+
+    /// Reads the document with the given identifier.
+    fn load(&self, id: &str) -> io::Result<String>;
+}
+
+impl<R> Resolver for &R
+where
+    R: Resolver + ?Sized,
+{
+    fn resolve(&self, path: &str, parent: Option<&str>) -> io::Result<String> {
+        (**self).resolve(path, parent)
+    }
+
+    fn expand(&self, pattern: &str, parent: Option<&str>) -> io::Result<Vec<String>> {
+        (**self).expand(pattern, parent)
+    }
+
+    fn load(&self, id: &str) -> io::Result<String> {
+        (**self).load(id)
+    }
+}
+
+/// Resolver that reads documents from the file system.
+///
+/// Paths starting with `~` are relative to the home directory and other
+/// relative paths are relative to the directory of the including document.
+/// Identifiers are canonical paths.
+#[derive(Clone, Debug)]
+pub struct FileResolver {
+    home: Option<PathBuf>,
+}
+
+impl FileResolver {
+    /// Creates a resolver that reads the home directory from the `HOME`
+    /// environment variable, or `USERPROFILE` on Windows.
+    #[must_use]
+    pub fn new() -> Self {
+        let home = env::var_os("HOME")
+            .or_else(|| env::var_os("USERPROFILE"))
+            .map(PathBuf::from);
+
+        Self { home }
+    }
+
+    /// Sets the directory that `~` expands to.
+    #[must_use]
+    pub fn with_home<P>(mut self, home: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.home = Some(home.into());
+        self
+    }
+}
+
+impl Default for FileResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileResolver {
+    fn join(&self, path: &str, parent: Option<&str>) -> io::Result<PathBuf> {
+        let path = expand_home(path, self.home.as_deref())?;
+
+        Ok(match parent {
+            Some(parent) if path.is_relative() => parent_dir(parent).join(path),
+            _ => path,
+        })
+    }
+}
+
+impl Resolver for FileResolver {
+    fn resolve(&self, path: &str, parent: Option<&str>) -> io::Result<String> {
+        into_id(fs::canonicalize(self.join(path, parent)?)?)
+    }
+
+    fn expand(&self, pattern: &str, parent: Option<&str>) -> io::Result<Vec<String>> {
+        let pattern = self.join(pattern, parent)?;
+        let mut paths = Vec::from([PathBuf::new()]);
+
+        for component in pattern.components() {
+            let component_pattern = component.as_os_str().to_str().unwrap_or_default();
+
+            if !glob::is_pattern(component_pattern) {
+                for path in &mut paths {
+                    path.push(component);
+                }
+
+                continue;
+            }
+
+            let mut matches = Vec::new();
+
+            for dir in paths.iter().filter(|path| path.is_dir()) {
+                for dir_entry in fs::read_dir(dir)? {
+                    let name = dir_entry?.file_name();
+
+                    if glob::matches(component_pattern, &name.to_string_lossy()) {
+                        matches.push(dir.join(name));
+                    }
+                }
+            }
+
+            paths = matches;
+        }
+
+        let mut ids = paths
+            .into_iter()
+            .filter(|path| path.is_file())
+            .map(|path| into_id(fs::canonicalize(path)?))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        ids.sort();
+        ids.dedup();
+        Ok(ids)
+    }
+
+    fn load(&self, id: &str) -> io::Result<String> {
+        fs::read_to_string(id)
+    }
+}
+
+/// Resolver that reads documents from memory, useful for tests.
+///
+/// Documents are identified by normalized paths, which are resolved like the
+/// paths of a [`FileResolver`].
+#[derive(Clone, Default, Debug)]
+pub struct MemoryResolver {
+    documents: BTreeMap<String, String>,
+    home: Option<PathBuf>,
+}
+
+impl MemoryResolver {
+    /// Creates a resolver without documents.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the directory that `~` expands to.
+    #[must_use]
+    pub fn with_home<P>(mut self, home: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.home = Some(home.into());
+        self
+    }
+
+    /// Adds a document at the given path.
+    #[must_use]
+    pub fn with_document<P, D>(mut self, path: P, document: D) -> Self
+    where
+        P: AsRef<Path>,
+        D: Into<String>,
+    {
+        self.insert(path, document);
+        self
+    }
+
+    /// Adds a document at the given path, replacing the previous document at
+    /// that path.
+    pub fn insert<P, D>(&mut self, path: P, document: D)
+    where
+        P: AsRef<Path>,
+        D: Into<String>,
+    {
+        let path = normalize(path.as_ref()).to_string_lossy().into_owned();
+        self.documents.insert(path, document.into());
+    }
+}
+
+impl MemoryResolver {
+    fn join(&self, path: &str, parent: Option<&str>) -> io::Result<PathBuf> {
+        let path = expand_home(path, self.home.as_deref())?;
+
+        Ok(normalize(&match parent {
+            Some(parent) if path.is_relative() => parent_dir(parent).join(path),
+            _ => path,
+        }))
+    }
+}
+
+impl Resolver for MemoryResolver {
+    fn resolve(&self, path: &str, parent: Option<&str>) -> io::Result<String> {
+        let id = self.join(path, parent)?.to_string_lossy().into_owned();
+
+        if !self.documents.contains_key(&id) {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("document not found: {id}"),
+            ));
+        }
+
+        Ok(id)
+    }
+
+    fn expand(&self, pattern: &str, parent: Option<&str>) -> io::Result<Vec<String>> {
+        let pattern = self.join(pattern, parent)?;
+
+        let ids = self
+            .documents
+            .keys()
+            .filter(|id| {
+                let mut components = Path::new(id).components();
+                let mut pattern_components = pattern.components();
+
+                loop {
+                    match (components.next(), pattern_components.next()) {
+                        (Some(component), Some(pattern_component)) => {
+                            let component = component.as_os_str().to_string_lossy();
+                            let pattern_component = pattern_component.as_os_str().to_string_lossy();
+
+                            if !glob::matches(&pattern_component, &component) {
+                                break false;
+                            }
+                        }
+                        (None, None) => break true,
+                        _ => break false,
+                    }
+                }
+            })
+            .cloned()
+            .collect();
+
+        Ok(ids)
+    }
+
+    fn load(&self, id: &str) -> io::Result<String> {
+        self.documents.get(id).cloned().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, format!("document not found: {id}"))
+        })
+    }
+}
+
+/// Loads documents and splices the documents they include in place of their
+/// include entries.
+///
+/// An include entry has the include key, [`imports`](DEFAULT_INCLUDE_KEY) by
+/// default, and either a single path or a list of paths as value. Include
+/// entries may appear at any level of nesting, in which case the entries of
+/// the included documents are nested at the same level. Included documents
+/// may include other documents, but not themselves.
+///
+/// Paths containing `*`, `?` or `[` are glob patterns which include every
+/// matching document in lexicographic order, for example `conf.d/*.ccl`. A
+/// pattern that matches no documents includes nothing.
+///
+/// The entries of included documents are [merged](Document::merge) into the
+/// including document: an included entry replaces the entry with the same key
+/// that precedes the include entry, and so does an entry that follows the
+/// include entry, so later entries always take precedence. Blocks are merged
+/// recursively and list items are appended.
+///
+/// ```
+/// use serde::Deserialize;
+/// use serde_ccl::{Loader, MemoryResolver};
+///
+/// let resolver = MemoryResolver::new()
+///     .with_document("/etc/app/app.ccl", "imports =\n  = theme.ccl\nfont_size = 12\n")
+///     .with_document("/etc/app/theme.ccl", "theme = dark\n");
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     theme: String,
+///     font_size: u32,
+/// }
+///
+/// let document = Loader::new(resolver).load("/etc/app/app.ccl").unwrap();
+/// let config = serde_ccl::from_document::<Config>(&document).unwrap();
+/// assert_eq!(config.theme, "dark");
+/// assert_eq!(config.font_size, 12);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Loader<R> {
+    resolver: R,
+    include_key: String,
+    allowed_roots: Vec<PathBuf>,
+}
+
+impl<R> Loader<R>
+where
+    R: Resolver,
+{
+    /// Creates a loader that reads documents with the given resolver.
+    #[must_use]
+    pub fn new(resolver: R) -> Self {
+        Self {
+            resolver,
+            include_key: DEFAULT_INCLUDE_KEY.into(),
+            allowed_roots: Vec::new(),
+        }
+    }
+
+    /// Sets the key of the entries that list the documents to include.
+    #[must_use]
+    pub fn with_include_key<K>(mut self, include_key: K) -> Self
+    where
+        K: Into<String>,
+    {
+        self.include_key = include_key.into();
+        self
+    }
+
+    /// Restricts included documents to the given root directory.
+    ///
+    /// Once at least one root is set, including a document whose identifier
+    /// is not inside one of the roots is an error. The root is compared with
+    /// the identifiers returned by the resolver, so it should be a canonical
+    /// path when using a [`FileResolver`]. The root document is not
+    /// restricted.
+    #[must_use]
+    pub fn with_allowed_root<P>(mut self, root: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.allowed_roots.push(root.into());
+        self
+    }
+
+    /// Returns the resolver used to read documents.
+    #[inline]
+    #[must_use]
+    pub fn resolver(&self) -> &R {
+        &self.resolver
+    }
+
+    /// Loads the document at the given path together with the documents it
+    /// includes.
+    pub fn load(&self, path: &str) -> Result<Document> {
+        self.load_with_includes(path).map(|(document, _)| document)
+    }
+
+    /// Loads the document at the given path together with the documents it
+    /// includes, and returns the identifiers of all loaded documents, starting
+    /// with the document at the given path.
+    pub fn load_with_includes(&self, path: &str) -> Result<(Document, Vec<String>)> {
+        let id = self.resolver.resolve(path, None).map_err(Error::io)?;
+        let mut includes = Includes::default();
+        let document = self.load_document(id, &mut includes)?;
+        Ok((document, includes.loaded))
+    }
+
+    /// Parses a document from a string and splices the documents it includes.
+    ///
+    /// Relative include paths are resolved relative to `path`.
+    pub fn load_str(&self, path: &str, text: &str) -> Result<Document> {
+        let mut includes = Includes {
+            stack: Vec::from([String::from(path)]),
+            loaded: Vec::new(),
+        };
+
+        let mut document = Document::parse_with_source(text, Source::named(path))?;
+        self.splice(&mut document, &mut includes)?;
+        Ok(document)
+    }
+
+    fn load_document(&self, id: String, includes: &mut Includes) -> Result<Document> {
+        if !includes.loaded.contains(&id) {
+            includes.loaded.push(id.clone());
+        }
+
+        let text = self
+            .resolver
+            .load(&id)
+            .map_err(|e| Error::io(e).with_input(Source::named(&id)))?;
+
+        let mut document = Document::parse_with_source(&text, Source::named(&id))?;
+
+        includes.stack.push(id);
+        self.splice(&mut document, includes)?;
+        includes.stack.pop();
+
+        Ok(document)
+    }
+
+    fn splice(&self, document: &mut Document, includes: &mut Includes) -> Result<()> {
+        let entries = core::mem::take(document.entries_mut());
+        let mut merge = false;
+
+        for mut entry in entries {
+            if entry.key() != self.include_key {
+                // Only splice blocks that include documents, so that the
+                // others keep the text they were parsed from.
+                if let Some(block) = entry
+                    .value_mut()
+                    .as_block_mut()
+                    .filter(|block| self.has_includes(block))
+                {
+                    self.splice(block, includes)?;
+                }
+
+                if merge {
+                    document.merge_entry(entry);
+                } else {
+                    document.push(entry);
+                }
+
+                continue;
+            }
+
+            for (path, span) in include_paths(&entry)? {
+                let parent = includes.stack.last().map(String::as_str);
+                let source = entry.origin().source().clone();
+
+                let error = |code| Error::new(code, span.location()).with_input(source.clone());
+
+                let ids = if glob::is_pattern(path) {
+                    self.resolver.expand(path, parent)
+                } else {
+                    self.resolver
+                        .resolve(path, parent)
+                        .map(|id| Vec::from([id]))
+                };
+
+                for id in ids.map_err(|e| error(ErrorCode::Io(e)))? {
+                    if !self.is_allowed(&id) {
+                        return Err(error(ErrorCode::IncludeNotAllowed(id)));
+                    }
+
+                    if includes.stack.contains(&id) {
+                        return Err(error(ErrorCode::IncludeCycle(id)));
+                    }
+
+                    document.merge(self.load_document(id, includes)?);
+                }
+            }
+
+            merge = true;
+        }
+
+        Ok(())
+    }
+
+    fn has_includes(&self, document: &Document) -> bool {
+        document.iter().any(|entry| {
+            entry.key() == self.include_key
+                || entry
+                    .value()
+                    .as_block()
+                    .is_some_and(|block| self.has_includes(block))
+        })
+    }
+
+    fn is_allowed(&self, id: &str) -> bool {
+        self.allowed_roots.is_empty()
+            || self
+                .allowed_roots
+                .iter()
+                .any(|root| Path::new(id).starts_with(root))
+    }
+}
+
+/// Documents being loaded and documents already loaded by a [`Loader`].
+#[derive(Default)]
+struct Includes {
+    stack: Vec<String>,
+    loaded: Vec<String>,
+}
+
+/// Returns the paths listed by an include entry together with their spans.
+fn include_paths(entry: &Entry) -> Result<Vec<(&str, Span)>> {
+    let invalid = |span: Span| {
+        Error::new(ErrorCode::InvalidInclude, span.location())
+            .with_input(entry.origin().source().clone())
+    };
+
+    match entry.value() {
+        Value::Text(path) if path.contains('\n') => Err(invalid(entry.origin().value_span())),
+        Value::Text(path) if path.is_empty() => Ok(Vec::new()),
+        Value::Text(path) => Ok(Vec::from([(path.as_str(), entry.origin().value_span())])),
+        Value::Block(block) => block
+            .entries()
+            .iter()
+            .filter(|item| item.key().is_empty())
+            .map(|item| match item.value() {
+                Value::Text(path) if !path.is_empty() && !path.contains('\n') => {
+                    Ok((path.as_str(), item.origin().value_span()))
+                }
+                _ => Err(invalid(item.origin().value_span())),
+            })
+            .collect(),
+    }
+}
+
+fn expand_home(path: &str, home: Option<&Path>) -> io::Result<PathBuf> {
+    let Some(rest) = path.strip_prefix('~') else {
+        return Ok(PathBuf::from(path));
+    };
+
+    if !(rest.is_empty() || rest.starts_with(['/', '\\'])) {
+        return Ok(PathBuf::from(path));
+    }
+
+    let home =
+        home.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "home directory is not known"))?;
+
+    Ok(home.join(rest.trim_start_matches(['/', '\\'])))
+}
+
+fn parent_dir(id: &str) -> &Path {
+    Path::new(id).parent().unwrap_or(Path::new(""))
+}
+
+fn into_id(path: PathBuf) -> io::Result<String> {
+    path.into_os_string()
+        .into_string()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "path is not valid UTF-8"))
+}
+
+/// Removes `.` and `..` components from a path without accessing the file
+/// system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
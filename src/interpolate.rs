@@ -0,0 +1,247 @@
+use crate::document::{Document, Origin, Value};
+use crate::error::{Error, ErrorCode, Result};
+use crate::path::KeyPath;
+use crate::position::Location;
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+
+/// Source of the variables substituted by [`Document::interpolate`].
+pub trait Lookup {
+    /// Returns the value of the variable with the given name, or `None` if the
+    /// variable is not defined.
+    fn lookup(&self, name: &str) -> Option<String>;
+}
+
+impl<L> Lookup for &L
+where
+    L: Lookup + ?Sized,
+{
+    fn lookup(&self, name: &str) -> Option<String> {
+        (**self).lookup(name)
+    }
+}
+
+impl<K, V> Lookup for BTreeMap<K, V>
+where
+    K: Borrow<str> + Ord,
+    V: AsRef<str>,
+{
+    fn lookup(&self, name: &str) -> Option<String> {
+        self.get(name).map(|value| value.as_ref().to_owned())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, V, S> Lookup for std::collections::HashMap<K, V, S>
+where
+    K: Borrow<str> + Eq + core::hash::Hash,
+    V: AsRef<str>,
+    S: core::hash::BuildHasher,
+{
+    fn lookup(&self, name: &str) -> Option<String> {
+        self.get(name).map(|value| value.as_ref().to_owned())
+    }
+}
+
+/// Lookup that reads the environment variables of the current process.
+///
+/// Variables whose values are not valid UTF-8 are not defined.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Default, Debug)]
+pub struct EnvLookup;
+
+#[cfg(feature = "std")]
+impl Lookup for EnvLookup {
+    fn lookup(&self, name: &str) -> Option<String> {
+        std::env::var(name).ok()
+    }
+}
+
+pub(crate) fn interpolate_document<L>(document: &mut Document, lookup: &L) -> Result<()>
+where
+    L: Lookup + ?Sized,
+{
+    for entry in document.entries_mut() {
+        let origin = entry.origin().clone();
+
+        match entry.value_mut() {
+            Value::Text(text) => {
+                *text = interpolate_text(text, &origin, &mut |name| {
+                    lookup.lookup(name).ok_or_else(|| {
+                        Failure::Undefined(ErrorCode::UndefinedVariable(name.into()))
+                    })
+                })?;
+            }
+            Value::Block(block) => interpolate_document(block, lookup)?,
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn resolve_document_references<L>(document: &mut Document, lookup: &L) -> Result<()>
+where
+    L: Lookup + ?Sized,
+{
+    let snapshot = document.clone();
+
+    References {
+        document: &snapshot,
+        lookup,
+        cache: BTreeMap::new(),
+        stack: Vec::new(),
+    }
+    .resolve_document(document)
+}
+
+/// Resolves references to the text values of a document by key path.
+struct References<'a, L: ?Sized> {
+    document: &'a Document,
+    lookup: &'a L,
+    cache: BTreeMap<String, String>,
+    stack: Vec<String>,
+}
+
+impl<L> References<'_, L>
+where
+    L: Lookup + ?Sized,
+{
+    fn resolve_document(&mut self, document: &mut Document) -> Result<()> {
+        for entry in document.entries_mut() {
+            let origin = entry.origin().clone();
+
+            match entry.value_mut() {
+                Value::Text(text) => *text = self.resolve_text(text, &origin)?,
+                Value::Block(block) => self.resolve_document(block)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn resolve_text(&mut self, text: &str, origin: &Origin) -> Result<String> {
+        interpolate_text(text, origin, &mut |name| match self.resolve_path(name)? {
+            Some(value) => Ok(value),
+            None => self
+                .lookup
+                .lookup(name)
+                .ok_or_else(|| Failure::Undefined(ErrorCode::UnresolvedReference(name.into()))),
+        })
+    }
+
+    fn resolve_path(&mut self, path: &str) -> core::result::Result<Option<String>, Failure> {
+        if let Some(value) = self.cache.get(path) {
+            return Ok(Some(value.clone()));
+        }
+
+        let Some(entry) = KeyPath::parse(path)
+            .ok()
+            .and_then(|key_path| self.document.get_path(&key_path))
+        else {
+            return Ok(None);
+        };
+
+        let Value::Text(text) = entry.value() else {
+            return Ok(None);
+        };
+
+        if self.stack.iter().any(|stacked| stacked == path) {
+            return Err(Failure::At(ErrorCode::ReferenceCycle(path.into())));
+        }
+
+        self.stack.push(path.into());
+        let value = self.resolve_text(text, entry.origin());
+        self.stack.pop();
+
+        let value = value.map_err(Failure::Error)?;
+        self.cache.insert(path.into(), value.clone());
+        Ok(Some(value))
+    }
+}
+
+/// Reason a variable could not be substituted.
+enum Failure {
+    /// The variable is not defined, which is an error located at the
+    /// variable unless it has a default value.
+    Undefined(ErrorCode),
+
+    /// Error located at the variable.
+    At(ErrorCode),
+
+    /// Error that occurred while substituting another value.
+    Error(Error),
+}
+
+/// Substitutes the variables in the text of an entry, with errors located at
+/// the variable that could not be substituted.
+fn interpolate_text(
+    text: &str,
+    origin: &Origin,
+    lookup: &mut dyn FnMut(&str) -> core::result::Result<String, Failure>,
+) -> Result<String> {
+    let error = |offset: usize, code| {
+        let location = if origin.is_parsed() {
+            origin.value_span().location().advance(&text[..offset])
+        } else {
+            Location::default()
+        };
+
+        Error::new(code, location).with_input(origin.source().clone())
+    };
+
+    let mut interpolated = String::with_capacity(text.len());
+    let mut last_end = 0;
+    let mut index = 0;
+
+    while let Some(offset) = memchr::memchr(b'$', &text.as_bytes()[index..]) {
+        let start = index + offset;
+        let rest = &text[start..];
+
+        if rest.starts_with("$${") {
+            interpolated.push_str(&text[last_end..start]);
+            interpolated.push_str("${");
+            index = start + 3;
+            last_end = index;
+            continue;
+        }
+
+        if !rest.starts_with("${") {
+            index = start + 1;
+            continue;
+        }
+
+        let Some(len) = rest.find('}') else {
+            return Err(error(start, ErrorCode::InvalidInterpolation));
+        };
+
+        let (name, default) = match rest[2..len].split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (&rest[2..len], None),
+        };
+
+        if name.is_empty() || name.contains(['$', '{', '\n']) {
+            return Err(error(start, ErrorCode::InvalidInterpolation));
+        }
+
+        let value = match (lookup(name), default) {
+            (Ok(value), Some(default)) if value.is_empty() => default.to_owned(),
+            (Ok(value), _) => value,
+            (Err(Failure::Undefined(_)), Some(default)) => default.to_owned(),
+            (Err(Failure::Undefined(code) | Failure::At(code)), _) => {
+                return Err(error(start, code));
+            }
+            (Err(Failure::Error(error)), _) => return Err(error),
+        };
+
+        interpolated.push_str(&text[last_end..start]);
+        interpolated.push_str(&value);
+        index = start + len + 1;
+        last_end = index;
+    }
+
+    interpolated.push_str(&text[last_end..]);
+    Ok(interpolated)
+}
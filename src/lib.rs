@@ -17,6 +17,23 @@
 //!
 //! # Features
 //! - `std` (on by default): link to the `std` crate.
+//! - `clap`: implement a [`clap`](https://crates.io/crates/clap) value parser
+//!   for [`Override`].
+//! - `cli`: build the `ccl` command-line tool, which formats documents with
+//!   `ccl fmt`, converts them to and from JSON, TOML and YAML with
+//!   `ccl convert`, reads values from them with `ccl get`, edits them with
+//!   `ccl set` and `ccl unset`, checks them with `ccl lint` and
+//!   `ccl validate`, compares them with `ccl diff` and merges them with
+//!   `ccl merge`.
+//! - `lsp`: build the `ccl-lsp` language server, which reports syntax errors
+//!   and lint problems, lists the keys of documents, folds nested blocks,
+//!   formats documents and goes to the documents named by includes.
+//! - `schema`: add a [`Schema`] for validating documents against a subset of
+//!   JSON Schema.
+//! - `schemars`: add [`Schema::for_type`] for generating the schema of a type
+//!   with [`schemars`](https://crates.io/crates/schemars), and [`example`] for
+//!   generating example documents with comments from its documentation.
+//! - `watch`: add a [`Watcher`] that reloads documents when they change.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
@@ -24,13 +41,106 @@
 extern crate alloc;
 
 pub(crate) mod de;
+pub(crate) mod diff;
+pub(crate) mod document;
+pub(crate) mod edit;
+#[cfg(feature = "std")]
+pub(crate) mod env;
 pub(crate) mod error;
+#[cfg(feature = "schemars")]
+pub(crate) mod example;
+pub(crate) mod format;
+#[cfg(feature = "std")]
+pub(crate) mod include;
+pub(crate) mod interpolate;
+pub(crate) mod line_index;
+pub(crate) mod lint;
+pub(crate) mod merge;
+pub(crate) mod overrides;
 pub(crate) mod parser;
+pub(crate) mod path;
 pub(crate) mod position;
+pub(crate) mod raw;
+#[cfg(feature = "schema")]
+pub(crate) mod schema;
+pub(crate) mod ser;
+pub(crate) mod spanned;
+#[cfg(feature = "watch")]
+pub(crate) mod watch;
+
+#[doc(inline)]
+pub use crate::diff::{diff, Change, ChangeKind};
+
+#[doc(inline)]
+pub use crate::document::{from_document, Document, Entry, Origin, Source, Value};
+
+#[doc(inline)]
+pub use crate::edit::Editor;
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use crate::env::{EnvOverrides, DEFAULT_ENV_SEPARATOR};
 
 #[doc(inline)]
 pub use crate::error::{Error, ErrorKind, Result};
 
+#[cfg(feature = "schemars")]
+#[doc(inline)]
+pub use crate::example::{example, example_of};
+
+#[doc(inline)]
+pub use crate::format::{format, format_with_source};
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use crate::include::{FileResolver, Loader, MemoryResolver, Resolver, DEFAULT_INCLUDE_KEY};
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use crate::interpolate::EnvLookup;
+
+#[doc(inline)]
+pub use crate::interpolate::Lookup;
+
+#[doc(inline)]
+pub use crate::line_index::{ColumnMode, LineIndex};
+
+#[doc(inline)]
+pub use crate::lint::{lint, lint_with_source, Diagnostic, Rule};
+
+#[doc(inline)]
+pub use crate::merge::{Assignment, Layers, ListPolicy, MergeOptions, Provenance};
+
+#[doc(inline)]
+pub use crate::overrides::Override;
+
+#[cfg(feature = "clap")]
+#[doc(inline)]
+pub use crate::overrides::OverrideValueParser;
+
+#[doc(inline)]
+pub use crate::path::{get, get_all, KeyPath, Segment};
+
+#[doc(inline)]
+pub use crate::position::Position;
+
+#[doc(inline)]
+pub use crate::raw::RawCcl;
+
+#[cfg(feature = "schema")]
+#[doc(inline)]
+pub use crate::schema::{Schema, Violation};
+
+#[doc(inline)]
+pub use crate::ser::{to_document, to_string};
+
+#[doc(inline)]
+pub use crate::spanned::{Span, Spanned};
+
+#[cfg(feature = "watch")]
+#[doc(inline)]
+pub use crate::watch::{WatchEvent, Watcher};
+
 use crate::de::Deserializer;
 use crate::parser::{SliceParser, StrParser};
 use serde_core::de::Deserialize;
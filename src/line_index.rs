@@ -0,0 +1,181 @@
+use crate::position::{Location, Position};
+use alloc::vec::Vec;
+
+/// Unit in which columns are measured.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum ColumnMode {
+    /// Columns are measured in bytes.
+    #[default]
+    Byte,
+
+    /// Columns are measured in Unicode scalar values.
+    Char,
+
+    /// Columns are measured in UTF-16 code units, as expected by the Language
+    /// Server Protocol.
+    Utf16,
+}
+
+/// Precomputed offsets of the lines in a document.
+///
+/// Converts byte offsets to line and column positions in `O(log n)` time, where
+/// `n` is the number of lines in the document.
+#[derive(Clone, Debug)]
+pub struct LineIndex<'a> {
+    data: &'a [u8],
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    /// Builds the line index of a document.
+    #[must_use]
+    pub fn new<D>(data: &'a D) -> Self
+    where
+        D: AsRef<[u8]> + ?Sized,
+    {
+        let data = data.as_ref();
+
+        let line_starts = core::iter::once(0)
+            .chain(memchr::memchr_iter(b'\n', data).map(|index| index + 1))
+            .collect();
+
+        Self { data, line_starts }
+    }
+
+    /// Returns the indexed document.
+    #[inline]
+    #[must_use]
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns the number of lines in the document.
+    #[inline]
+    #[must_use]
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Returns the byte offset at which the given line starts, if the line
+    /// exists. Lines start at 1.
+    #[must_use]
+    pub fn line_start(&self, line: usize) -> Option<usize> {
+        self.line_starts.get(line.checked_sub(1)?).copied()
+    }
+
+    /// Returns the contents of the given line without the trailing newline, if
+    /// the line exists. Lines start at 1.
+    #[must_use]
+    pub fn line_data(&self, line: usize) -> Option<&'a [u8]> {
+        let start = self.line_start(line)?;
+
+        let end = self
+            .line_starts
+            .get(line)
+            .map_or(self.data.len(), |next_start| next_start - 1);
+
+        Some(&self.data[start..end])
+    }
+
+    /// Returns the line that contains the byte at the given offset. Offsets
+    /// past the end of the document are clamped to the end of the document.
+    #[must_use]
+    pub fn line(&self, index: usize) -> usize {
+        let index = index.min(self.data.len());
+
+        match self.line_starts.binary_search(&index) {
+            Ok(line) => line + 1,
+            Err(line) => line,
+        }
+    }
+
+    /// Returns the position of the byte at the given offset, with the column
+    /// measured in the given mode. Offsets past the end of the document are
+    /// clamped to the end of the document.
+    #[must_use]
+    pub fn position(&self, index: usize, mode: ColumnMode) -> Position {
+        let index = index.min(self.data.len());
+        let line = self.line(index);
+        let line_start = self.line_starts[line - 1];
+
+        Position {
+            line,
+            column: 1 + column_width(&self.data[line_start..index], mode),
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn location(&self, index: usize) -> Location {
+        let index = index.min(self.data.len());
+        let line = self.line(index);
+        let line_data = &self.data[self.line_starts[line - 1]..index];
+
+        Location {
+            index,
+            line,
+            column: 1 + column_width(line_data, ColumnMode::Byte),
+            column_chars: 1 + column_width(line_data, ColumnMode::Char),
+            column_utf16: 1 + column_width(line_data, ColumnMode::Utf16),
+        }
+    }
+
+    /// Returns the byte offset of the given position, with the column measured
+    /// in the given mode, if the position is inside the document. Columns past
+    /// the end of a line are clamped to the end of the line.
+    #[must_use]
+    pub fn index(&self, position: Position, mode: ColumnMode) -> Option<usize> {
+        let line_data = self.line_data(position.line)?;
+        let line_start = self.line_starts[position.line - 1];
+        let column = position.column.checked_sub(1)?;
+
+        if mode == ColumnMode::Byte {
+            return Some(line_start + column.min(line_data.len()));
+        }
+
+        let mut width = 0;
+
+        for (offset, &byte) in line_data.iter().enumerate() {
+            if !is_char_boundary(byte) {
+                continue;
+            }
+
+            if width >= column {
+                return Some(line_start + offset);
+            }
+
+            width += char_width(byte, mode);
+        }
+
+        Some(line_start + line_data.len())
+    }
+}
+
+#[must_use]
+fn column_width(data: &[u8], mode: ColumnMode) -> usize {
+    match mode {
+        ColumnMode::Byte => data.len(),
+        ColumnMode::Char | ColumnMode::Utf16 => data
+            .iter()
+            .filter(|&&byte| is_char_boundary(byte))
+            .map(|&byte| char_width(byte, mode))
+            .sum(),
+    }
+}
+
+#[inline]
+#[must_use]
+fn is_char_boundary(byte: u8) -> bool {
+    // Continuation bytes have the form `0b10xx_xxxx`.
+    byte & 0xC0 != 0x80
+}
+
+#[inline]
+#[must_use]
+fn char_width(leading_byte: u8, mode: ColumnMode) -> usize {
+    match mode {
+        ColumnMode::Byte | ColumnMode::Char => 1,
+        // Only characters encoded with 4 bytes in UTF-8 need a surrogate pair
+        // in UTF-16.
+        ColumnMode::Utf16 => 1 + usize::from(leading_byte >= 0xF0),
+    }
+}
@@ -0,0 +1,395 @@
+use crate::document::{Document, Source, Value};
+use crate::error::{Error, ErrorCode, Result};
+use crate::line_index::LineIndex;
+use crate::position::Location;
+use crate::spanned::Span;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::RangeInclusive;
+use core::str::FromStr;
+
+/// Prefix of the comments that disable rules.
+const DIRECTIVE: &str = "ccl-lint:";
+
+/// Rule checked by [`lint`].
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Rule {
+    /// A key other than a list item or a comment is repeated in a block.
+    DuplicateKey,
+
+    /// An entry is indented differently than the first entry of its block.
+    InconsistentIndent,
+
+    /// The indentation of a line contains tabs, which are not indentation in
+    /// CCL.
+    MixedIndent,
+
+    /// A line ends with spaces or tabs.
+    TrailingWhitespace,
+
+    /// A line has no equal sign, so it is read as part of the key of the next
+    /// entry.
+    MissingEq,
+
+    /// A comment is between the items of a list.
+    CommentInList,
+}
+
+impl Rule {
+    /// All rules, in the order in which they are documented.
+    pub const ALL: [Rule; 6] = [
+        Rule::DuplicateKey,
+        Rule::InconsistentIndent,
+        Rule::MixedIndent,
+        Rule::TrailingWhitespace,
+        Rule::MissingEq,
+        Rule::CommentInList,
+    ];
+
+    /// Returns the name of the rule, as used in comments that disable it.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Rule::DuplicateKey => "duplicate-key",
+            Rule::InconsistentIndent => "inconsistent-indent",
+            Rule::MixedIndent => "mixed-indent",
+            Rule::TrailingWhitespace => "trailing-whitespace",
+            Rule::MissingEq => "missing-eq",
+            Rule::CommentInList => "comment-in-list",
+        }
+    }
+
+    /// Returns a short description of what the rule checks.
+    #[must_use]
+    pub fn description(self) -> &'static str {
+        match self {
+            Rule::DuplicateKey => "Keys are not repeated in a block",
+            Rule::InconsistentIndent => "Entries of a block are indented alike",
+            Rule::MixedIndent => "Indentation only contains spaces",
+            Rule::TrailingWhitespace => "Lines do not end with whitespace",
+            Rule::MissingEq => "Lines that start entries have an equal sign",
+            Rule::CommentInList => "Comments are not between list items",
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Rule {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        Rule::ALL
+            .into_iter()
+            .find(|rule| rule.name() == name)
+            .ok_or_else(|| Error::new(ErrorCode::UnknownRule(name.into()), Location::default()))
+    }
+}
+
+/// Problem found by [`lint`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Diagnostic {
+    rule: Rule,
+    message: String,
+    span: Span,
+}
+
+impl Diagnostic {
+    /// Returns the rule that found the problem.
+    #[inline]
+    #[must_use]
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Returns the description of the problem.
+    #[inline]
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the span of the text with the problem.
+    #[inline]
+    #[must_use]
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} at line {} column {}",
+            self.rule,
+            self.message,
+            self.span.line(),
+            self.span.column(),
+        )
+    }
+}
+
+/// Checks a CCL document for likely mistakes, returning the problems found
+/// ordered by position.
+///
+/// Rules are disabled with comments starting with `ccl-lint:`, followed by
+/// `disable` to disable rules until the end of the block that contains the
+/// comment, or by `disable-next-line` to disable rules on the next line, and
+/// then by the names of the rules, or by nothing to disable every rule.
+///
+/// ```
+/// use serde_ccl::Rule;
+///
+/// let text = "\
+/// port = 80
+/// port = 8080
+/// hosts =
+///   = a.com
+///   /= ccl-lint: disable-next-line comment-in-list
+///   /= Backup
+///   = b.com
+/// ";
+///
+/// let diagnostics = serde_ccl::lint(text).unwrap();
+/// let [diagnostic] = diagnostics.as_slice() else {
+///     panic!("expected a single diagnostic");
+/// };
+///
+/// assert_eq!(diagnostic.rule(), Rule::DuplicateKey);
+/// assert_eq!(diagnostic.to_string(), "duplicate-key: duplicate key `port`, first defined on line 1 at line 2 column 1");
+/// ```
+pub fn lint(text: &str) -> Result<Vec<Diagnostic>> {
+    lint_with_source(text, Source::Anonymous)
+}
+
+/// Checks a CCL document for likely mistakes, recording the given source in
+/// parse errors.
+pub fn lint_with_source(text: &str, source: Source) -> Result<Vec<Diagnostic>> {
+    let (document, missing_eqs) = Document::parse_recovering(text, source)?;
+
+    let mut linter = Linter {
+        line_index: LineIndex::new(text),
+        diagnostics: Vec::new(),
+        suppressions: Vec::new(),
+    };
+
+    linter.lint_lines();
+
+    for missing_eq in missing_eqs {
+        let message = match missing_eq.key_line {
+            Some(key_line) => {
+                format!("missing equal sign, so the line is part of the key on line {key_line}")
+            }
+            None => "missing equal sign".into(),
+        };
+
+        linter.report(
+            Rule::MissingEq,
+            message,
+            missing_eq.range.start,
+            missing_eq.range.end,
+        );
+    }
+
+    linter.lint_block(&document, linter.line_index.line_count());
+
+    let Linter {
+        mut diagnostics,
+        suppressions,
+        ..
+    } = linter;
+
+    diagnostics.retain(|diagnostic| {
+        !suppressions
+            .iter()
+            .any(|suppression| suppression.applies_to(diagnostic))
+    });
+
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.span.start(), diagnostic.rule));
+    Ok(diagnostics)
+}
+
+struct Linter<'a> {
+    line_index: LineIndex<'a>,
+    diagnostics: Vec<Diagnostic>,
+    suppressions: Vec<Suppression>,
+}
+
+impl Linter<'_> {
+    fn report(&mut self, rule: Rule, message: String, start: usize, end: usize) {
+        self.diagnostics.push(Diagnostic {
+            rule,
+            message,
+            span: Span::new(self.line_index.location(start), end),
+        });
+    }
+
+    /// Checks the whitespace of every line.
+    fn lint_lines(&mut self) {
+        for line in 1..=self.line_index.line_count() {
+            let start = self.line_index.line_start(line).unwrap_or_default();
+            let data = self.line_index.line_data(line).unwrap_or_default();
+            let data = data.strip_suffix(b"\r").unwrap_or(data);
+
+            let content = data.trim_ascii_end();
+            let content_end = start + content.len();
+
+            if content.len() < data.len() {
+                self.report(
+                    Rule::TrailingWhitespace,
+                    "trailing whitespace".into(),
+                    content_end,
+                    start + data.len(),
+                );
+            }
+
+            let indent = &content[..content.len() - content.trim_ascii_start().len()];
+
+            if indent.contains(&b'\t') {
+                let message = if indent.contains(&b' ') {
+                    "indentation mixes tabs and spaces"
+                } else {
+                    "indentation uses tabs instead of spaces"
+                };
+
+                self.report(
+                    Rule::MixedIndent,
+                    message.into(),
+                    start,
+                    start + indent.len(),
+                );
+            }
+        }
+    }
+
+    /// Checks the entries of the block, which ends on the given line.
+    fn lint_block(&mut self, block: &Document, end_line: usize) {
+        let indent = block
+            .entries()
+            .first()
+            .map(|entry| entry.origin().key_span().column());
+
+        let is_list = block.is_list();
+
+        let mut first_lines = BTreeMap::new();
+
+        for (i, entry) in block.iter().enumerate() {
+            let origin = entry.origin();
+            let key_span = origin.key_span();
+            let end = origin.value_span().end().max(key_span.end());
+
+            if let Some(indent) = indent.filter(|indent| *indent != key_span.column()) {
+                self.report(
+                    Rule::InconsistentIndent,
+                    format!(
+                        "expected indentation of {} spaces, found {}",
+                        indent - 1,
+                        key_span.column() - 1,
+                    ),
+                    key_span.start(),
+                    key_span.end(),
+                );
+            }
+
+            match entry.key() {
+                "" => (),
+                "/" => {
+                    let value = entry.value().as_text().unwrap_or_default();
+
+                    if let Some(mut suppression) = Suppression::parse(value) {
+                        suppression.lines = if suppression.next_line {
+                            let line = self.line_index.line(end) + 1;
+                            line..=line
+                        } else {
+                            key_span.line()..=end_line
+                        };
+
+                        self.suppressions.push(suppression);
+                    } else if is_list
+                        && block.entries()[..i]
+                            .iter()
+                            .any(|entry| entry.key().is_empty())
+                        && block.entries()[i..]
+                            .iter()
+                            .any(|entry| entry.key().is_empty())
+                    {
+                        self.report(
+                            Rule::CommentInList,
+                            "comment between list items".into(),
+                            key_span.start(),
+                            end,
+                        );
+                    }
+                }
+                key => {
+                    if let Some(first_line) = first_lines.get(key) {
+                        self.report(
+                            Rule::DuplicateKey,
+                            format!("duplicate key `{key}`, first defined on line {first_line}"),
+                            key_span.start(),
+                            key_span.end(),
+                        );
+                    } else {
+                        first_lines.insert(key, key_span.line());
+                    }
+                }
+            }
+
+            if let Value::Block(block) = entry.value() {
+                self.lint_block(block, self.line_index.line(end));
+            }
+        }
+    }
+}
+
+/// Rules disabled on a range of lines by a comment.
+struct Suppression {
+    rules: Option<Vec<Rule>>,
+    next_line: bool,
+    lines: RangeInclusive<usize>,
+}
+
+impl Suppression {
+    /// Parses the text of a comment that disables rules. Unknown rules are
+    /// ignored.
+    fn parse(comment: &str) -> Option<Self> {
+        let comment = comment.strip_prefix(DIRECTIVE)?.trim_start();
+        let mut words = comment
+            .split([' ', ','])
+            .filter(|word| !word.is_empty())
+            .peekable();
+
+        let next_line = match words.next()? {
+            "disable" => false,
+            "disable-next-line" => true,
+            _ => return None,
+        };
+
+        let rules = words
+            .peek()
+            .is_some()
+            .then(|| words.filter_map(|word| word.parse().ok()).collect());
+
+        Some(Self {
+            rules,
+            next_line,
+            lines: 0..=0,
+        })
+    }
+
+    fn applies_to(&self, diagnostic: &Diagnostic) -> bool {
+        self.lines.contains(&diagnostic.span.line())
+            && self
+                .rules
+                .as_ref()
+                .is_none_or(|rules| rules.contains(&diagnostic.rule))
+    }
+}
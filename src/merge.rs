@@ -0,0 +1,322 @@
+use crate::document::{from_document, Document, Origin, Value};
+use crate::error::Result;
+use crate::path::{KeyPath, Segment};
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Bound;
+use serde_core::de::DeserializeOwned;
+
+/// How lists are merged when a later document also has list items.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum ListPolicy {
+    /// Append the list items of the later document.
+    #[default]
+    Append,
+
+    /// Replace the list items of the earlier document with the list items of
+    /// the later document.
+    Replace,
+}
+
+/// Options for [`Document::merge_with`] and [`Layers`].
+///
+/// Text values are always replaced by the value of the later document and
+/// blocks are merged recursively.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct MergeOptions {
+    list_policy: ListPolicy,
+}
+
+impl MergeOptions {
+    /// Creates the default merge options.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            list_policy: ListPolicy::Append,
+        }
+    }
+
+    /// Sets how list items are merged.
+    #[must_use]
+    pub const fn with_list_policy(mut self, list_policy: ListPolicy) -> Self {
+        self.list_policy = list_policy;
+        self
+    }
+
+    /// Returns how list items are merged.
+    #[inline]
+    #[must_use]
+    pub const fn list_policy(&self) -> ListPolicy {
+        self.list_policy
+    }
+}
+
+/// Named documents merged in priority order, from lowest to highest.
+///
+/// ```
+/// use serde::Deserialize;
+/// use serde_ccl::{Document, Layers, ListPolicy, MergeOptions};
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     port: u16,
+///     hosts: Vec<String>,
+/// }
+///
+/// let defaults = Document::parse("port = 80\nhosts =\n  = localhost\n").unwrap();
+/// let user = Document::parse("hosts =\n  = example.com\n").unwrap();
+/// let overrides = Document::parse("port = 8080\n").unwrap();
+///
+/// let config = Layers::new()
+///     .with_options(MergeOptions::new().with_list_policy(ListPolicy::Replace))
+///     .with_layer("defaults", defaults)
+///     .with_layer("user", user)
+///     .with_layer("overrides", overrides)
+///     .deserialize::<Config>()
+///     .unwrap();
+///
+/// assert_eq!(config.port, 8080);
+/// assert_eq!(config.hosts, ["example.com"]);
+/// ```
+#[derive(Clone, Default, Debug)]
+pub struct Layers {
+    layers: Vec<(String, Document)>,
+    options: MergeOptions,
+}
+
+impl Layers {
+    /// Creates an empty stack of layers with the default merge options.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the options used to merge the layers.
+    #[must_use]
+    pub fn with_options(mut self, options: MergeOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Adds a layer that takes precedence over the previous layers.
+    #[must_use]
+    pub fn with_layer<N>(mut self, name: N, document: Document) -> Self
+    where
+        N: Into<String>,
+    {
+        self.push(name, document);
+        self
+    }
+
+    /// Adds a layer that takes precedence over the previous layers.
+    pub fn push<N>(&mut self, name: N, document: Document)
+    where
+        N: Into<String>,
+    {
+        self.layers.push((name.into(), document));
+    }
+
+    /// Returns the options used to merge the layers.
+    #[inline]
+    #[must_use]
+    pub fn options(&self) -> MergeOptions {
+        self.options
+    }
+
+    /// Returns an iterator over the names and documents of the layers, from
+    /// lowest to highest priority.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Document)> {
+        self.layers
+            .iter()
+            .map(|(name, document)| (name.as_str(), document))
+    }
+
+    /// Returns the number of layers.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Returns whether there are no layers.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Merges the layers into a single document.
+    #[must_use]
+    pub fn merge(&self) -> Document {
+        self.clone().into_document()
+    }
+
+    /// Merges the layers into a single document, consuming them.
+    #[must_use]
+    pub fn into_document(self) -> Document {
+        let mut merged = Document::new();
+
+        for (_, document) in self.layers {
+            merged.merge_with(document, self.options);
+        }
+
+        merged
+    }
+
+    /// Merges the layers into a single document and records the origin of
+    /// each setting.
+    ///
+    /// ```
+    /// use serde_ccl::{Document, Layers, Source};
+    ///
+    /// let system = Document::parse_with_source("port = 80\n", Source::named("/etc/app.ccl"));
+    /// let user = Document::parse_with_source("\nport = 8080\n", Source::named("~/.app.ccl"));
+    ///
+    /// let layers = Layers::new()
+    ///     .with_layer("system", system.unwrap())
+    ///     .with_layer("user", user.unwrap());
+    ///
+    /// let (_, provenance) = layers.merge_with_provenance();
+    /// let port = provenance.get(&"port".parse().unwrap()).unwrap();
+    /// assert_eq!(port.layer(), "user");
+    /// assert_eq!(port.origin().key_span().line(), 2);
+    /// assert_eq!(port.to_string(), "~/.app.ccl:2:1 (user)");
+    ///
+    /// let overridden = &provenance.history(&"port".parse().unwrap())[0];
+    /// assert_eq!(overridden.layer(), "system");
+    /// ```
+    #[must_use]
+    pub fn merge_with_provenance(&self) -> (Document, Provenance) {
+        let mut provenance = Provenance::default();
+        let mut path = Vec::new();
+
+        for (name, document) in &self.layers {
+            provenance.record(name, document, &mut path);
+        }
+
+        (self.merge(), provenance)
+    }
+
+    /// Deserializes the value from the merged layers.
+    pub fn deserialize<T>(&self) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        from_document(&self.merge())
+    }
+}
+
+/// Origins of the settings of merged [`Layers`], indexed by [`KeyPath`].
+///
+/// Key paths are made of the keys leading to a setting, such as
+/// `server.port`. List items and comments are not recorded individually, but
+/// every layer that contributes list items is recorded for the key of the
+/// list.
+#[derive(Clone, Default, Debug)]
+pub struct Provenance {
+    paths: BTreeMap<KeyPath, Vec<Assignment>>,
+}
+
+impl Provenance {
+    /// Returns the assignment that supplied the value at the given path.
+    #[must_use]
+    pub fn get(&self, path: &KeyPath) -> Option<&Assignment> {
+        self.paths.get(path)?.last()
+    }
+
+    /// Returns the assignments of the value at the given path, from the first
+    /// overridden assignment to the one that supplied the value.
+    #[must_use]
+    pub fn history(&self, path: &KeyPath) -> &[Assignment] {
+        self.paths.get(path).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns an iterator over the paths and the assignments that supplied
+    /// their values, with the paths in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&KeyPath, &Assignment)> {
+        self.paths
+            .iter()
+            .filter_map(|(path, history)| Some((path, history.last()?)))
+    }
+
+    fn record(&mut self, layer: &str, document: &Document, path: &mut Vec<Segment>) {
+        for entry in document {
+            if entry.key().is_empty() || entry.key() == "/" {
+                continue;
+            }
+
+            path.push(Segment::Key(entry.key().into()));
+            let key_path = path.iter().cloned().collect::<KeyPath>();
+
+            match entry.value() {
+                Value::Block(block) => self.record(layer, block, path),
+                Value::Text(_) => {
+                    // The paths under the replaced block follow the path of
+                    // the block itself.
+                    let nested = self
+                        .paths
+                        .range((Bound::Excluded(&key_path), Bound::Unbounded))
+                        .map(|(nested, _)| nested)
+                        .take_while(|nested| nested.segments().starts_with(path))
+                        .cloned()
+                        .collect::<Vec<_>>();
+
+                    for nested in nested {
+                        self.paths.remove(&nested);
+                    }
+                }
+            }
+
+            self.paths.entry(key_path).or_default().push(Assignment {
+                layer: layer.into(),
+                origin: entry.origin().clone(),
+            });
+
+            path.pop();
+        }
+    }
+}
+
+/// Assignment of a value by one of the merged [`Layers`].
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Assignment {
+    layer: String,
+    origin: Origin,
+}
+
+impl Assignment {
+    /// Returns the name of the layer that assigned the value.
+    #[inline]
+    #[must_use]
+    pub fn layer(&self) -> &str {
+        &self.layer
+    }
+
+    /// Returns the location of the entry that assigned the value.
+    #[inline]
+    #[must_use]
+    pub fn origin(&self) -> &Origin {
+        &self.origin
+    }
+}
+
+impl fmt::Display for Assignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let span = self.origin.key_span();
+
+        if self.origin.is_parsed() {
+            write!(
+                f,
+                "{}:{}:{} ({})",
+                self.origin.source(),
+                span.line(),
+                span.column(),
+                self.layer,
+            )
+        } else {
+            write!(f, "{} ({})", self.origin.source(), self.layer)
+        }
+    }
+}
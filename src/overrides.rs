@@ -0,0 +1,153 @@
+use crate::document::{Document, Origin, Source};
+use crate::error::{Error, ErrorCode, Result};
+use crate::path::{location, KeyPath};
+use crate::spanned::Span;
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+/// Assignment of a value to a key path, written as `path=value`, such as the
+/// argument of a `--set` command-line option.
+///
+/// The path is a [`KeyPath`], so `hosts[]=example.com` appends an item to the
+/// `hosts` list and `servers[0].port=8080` sets the port of its first item.
+/// Errors in the assignment and in the deserialization of its value name the
+/// argument it was parsed from.
+///
+/// ```
+/// use serde::Deserialize;
+/// use serde_ccl::{Document, Override};
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     port: u16,
+///     hosts: Vec<String>,
+/// }
+///
+/// let mut document = Document::parse("port = 80\nhosts =\n  = a.com\n").unwrap();
+///
+/// for arg in ["port=8080", "hosts[]=b.com"] {
+///     arg.parse::<Override>().unwrap().apply(&mut document).unwrap();
+/// }
+///
+/// let config = serde_ccl::from_document::<Config>(&document).unwrap();
+/// assert_eq!(config.port, 8080);
+/// assert_eq!(config.hosts, ["a.com", "b.com"]);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Override {
+    path: KeyPath,
+    value: String,
+    source: Source,
+}
+
+impl Override {
+    /// Parses an override written as `path=value`.
+    pub fn parse(arg: &str) -> Result<Self> {
+        let source = Source::argument(arg);
+
+        let Some((path, value)) = arg.split_once('=') else {
+            return Err(Error::new(ErrorCode::ExpectedEq, location(arg)).with_input(source));
+        };
+
+        let path = KeyPath::parse(path).map_err(|e| e.with_input(source.clone()))?;
+
+        Ok(Self {
+            path,
+            value: value.into(),
+            source,
+        })
+    }
+
+    /// Returns the path of the value to set.
+    #[inline]
+    #[must_use]
+    pub fn path(&self) -> &KeyPath {
+        &self.path
+    }
+
+    /// Returns the value to set.
+    #[inline]
+    #[must_use]
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Sets the value at the path of the override in the given document.
+    pub fn apply(&self, document: &mut Document) -> Result<()> {
+        let entry = document
+            .set_path(&self.path, self.value.as_str())
+            .map_err(|e| e.with_input(self.source.clone()))?;
+
+        *entry.origin_mut() = Origin::new(self.source.clone(), Span::default(), Span::default());
+        Ok(())
+    }
+}
+
+impl FromStr for Override {
+    type Err = Error;
+
+    fn from_str(arg: &str) -> Result<Self> {
+        Self::parse(arg)
+    }
+}
+
+impl fmt::Display for Override {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.path, self.value)
+    }
+}
+
+#[cfg(feature = "clap")]
+impl clap::builder::ValueParserFactory for Override {
+    type Parser = OverrideValueParser;
+
+    fn value_parser() -> Self::Parser {
+        OverrideValueParser
+    }
+}
+
+/// [`clap`] value parser for [`Override`] arguments.
+///
+/// ```
+/// use clap::{value_parser, Arg, ArgAction, Command};
+/// use serde_ccl::Override;
+///
+/// let command = Command::new("app").arg(
+///     Arg::new("set")
+///         .long("set")
+///         .action(ArgAction::Append)
+///         .value_parser(value_parser!(Override)),
+/// );
+///
+/// let matches = command.try_get_matches_from(["app", "--set", "port=8080"]).unwrap();
+/// let overrides = matches.get_many::<Override>("set").unwrap();
+/// assert_eq!(overrides.map(Override::value).collect::<Vec<_>>(), ["8080"]);
+/// ```
+#[cfg(feature = "clap")]
+#[derive(Clone, Copy, Default, Debug)]
+pub struct OverrideValueParser;
+
+#[cfg(feature = "clap")]
+impl clap::builder::TypedValueParser for OverrideValueParser {
+    type Value = Override;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        _arg: Option<&clap::Arg>,
+        value: &std::ffi::OsStr,
+    ) -> core::result::Result<Self::Value, clap::Error> {
+        let value = value
+            .to_str()
+            .ok_or_else(|| clap::Error::new(clap::error::ErrorKind::InvalidUtf8).with_cmd(cmd))?;
+
+        Override::parse(value).map_err(|e| {
+            clap::Error::raw(
+                clap::error::ErrorKind::ValueValidation,
+                format_args!("{e}\n"),
+            )
+            .with_cmd(cmd)
+        })
+    }
+}
@@ -5,7 +5,8 @@ pub(crate) use self::slice_parser::*;
 pub(crate) use self::str_parser::*;
 
 use crate::error::Result;
-use crate::position::Position;
+use crate::line_index::LineIndex;
+use crate::position::Location;
 use core::str;
 
 pub(crate) trait Parser<'a> {
@@ -13,14 +14,28 @@ pub(crate) trait Parser<'a> {
 
     fn parse_value(&mut self) -> Result<&'a str>;
 
+    fn parse_remaining(&mut self) -> Result<&'a str>;
+
     fn skip_whitespace(&mut self) -> Result<IndentState>;
 
     #[must_use]
     fn data(&self) -> &'a [u8];
 
+    #[must_use]
+    fn line_index(&self) -> &LineIndex<'a>;
+
+    #[must_use]
+    fn index(&self) -> usize;
+
     #[must_use]
     fn last_key_index(&self) -> usize;
 
+    #[must_use]
+    fn last_key_end(&self) -> usize;
+
+    #[must_use]
+    fn last_value_end(&self) -> usize;
+
     #[must_use]
     fn last_key_indent(&self) -> u32;
 
@@ -30,22 +45,13 @@ pub(crate) trait Parser<'a> {
     }
 
     #[must_use]
-    fn position_of_index(&self, index: usize) -> Position {
-        // Adapted from serde_json: https://github.com/serde-rs/json.
-
-        let data = self.data();
-        let start_of_line =
-            memchr::memrchr(b'\n', &data[..index]).map_or(0, |position| position + 1);
-
-        Position {
-            line: 1 + memchr::memchr_iter(b'\n', &data[..start_of_line]).count(),
-            column: 1 + index - start_of_line,
-        }
+    fn location_of_index(&self, index: usize) -> Location {
+        self.line_index().location(index)
     }
 
     #[must_use]
-    unsafe fn position_of_ptr(&self, ptr: *const u8) -> Position {
-        self.position_of_index(self.index_of_ptr(ptr))
+    unsafe fn location_of_ptr(&self, ptr: *const u8) -> Location {
+        self.location_of_index(self.index_of_ptr(ptr))
     }
 }
 
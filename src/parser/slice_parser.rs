@@ -1,13 +1,18 @@
 use crate::error::{Error, ErrorCode, Result};
+use crate::line_index::LineIndex;
 use crate::parser::{IndentState, Parser};
+use core::cell::OnceCell;
 use core::str;
 
 #[must_use]
 pub(crate) struct SliceParser<'a> {
     data: &'a [u8],
+    line_index: OnceCell<LineIndex<'a>>,
     index: usize,
     last_key_index: usize,
+    last_key_end: usize,
     last_key_indent: u32,
+    last_value_end: usize,
     indent_state: IndentState,
 }
 
@@ -15,9 +20,12 @@ impl<'a> SliceParser<'a> {
     pub fn new(data: &'a [u8]) -> Self {
         Self {
             data,
+            line_index: OnceCell::new(),
             index: 0,
             last_key_indent: 0,
             last_key_index: 0,
+            last_key_end: 0,
+            last_value_end: 0,
             indent_state: IndentState::Start(0),
         }
     }
@@ -29,34 +37,26 @@ impl<'a> SliceParser<'a> {
         }
 
         let key_start = self.index;
-        let mut found_eq = false;
 
-        while self.index < self.data.len() {
-            if self.data[self.index] == b'=' {
-                found_eq = true;
-                break;
-            }
-
-            self.index += 1;
-        }
-
-        if !found_eq {
-            let position = self.position_of_index(self.index);
-            return Err(Error::new(ErrorCode::ExpectedEq, position));
-        }
+        let Some(eq_offset) = memchr::memchr(b'=', &self.data[key_start..]) else {
+            self.index = self.data.len();
+            let location = self.location_of_index(self.index);
+            return Err(Error::new(ErrorCode::ExpectedEq, location));
+        };
 
-        let key_end = self.index;
-        self.index += 1;
+        let key_end = key_start + eq_offset;
+        self.index = key_end + 1;
 
         let key = trim(&self.data[key_start..key_end]);
         self.last_key_index = unsafe { self.index_of_ptr(key.as_ptr()) };
+        self.last_key_end = self.last_key_index + key.len();
         Ok(key)
     }
 
     pub fn parse_value_raw(&mut self) -> &'a [u8] {
         let value_start = self.index;
 
-        while self.index < self.data.len() {
+        loop {
             match self.skip_whitespace_raw() {
                 IndentState::Start(indent) => {
                     if indent <= self.last_key_indent {
@@ -69,16 +69,28 @@ impl<'a> SliceParser<'a> {
                 IndentState::Eof => break,
             }
 
-            while self.index < self.data.len() {
-                if self.data[self.index] == b'\n' {
-                    break;
-                }
-
-                self.index += 1;
-            }
+            self.skip_line_raw();
         }
 
-        trim(&self.data[value_start..self.index])
+        let value = trim(&self.data[value_start..self.index]);
+        self.last_value_end = unsafe { self.index_of_ptr(value.as_ptr()) } + value.len();
+        value
+    }
+
+    pub fn parse_remaining_raw(&mut self) -> &'a [u8] {
+        let remaining = trim(&self.data[self.index..]);
+        self.index = self.data.len();
+        self.indent_state = IndentState::Eof;
+        self.last_value_end = unsafe { self.index_of_ptr(remaining.as_ptr()) } + remaining.len();
+        remaining
+    }
+
+    /// Advances the parser to the next newline character or to the end of the
+    /// data if the current line is the last one.
+    #[inline]
+    pub fn skip_line_raw(&mut self) {
+        self.index = memchr::memchr(b'\n', &self.data[self.index..])
+            .map_or(self.data.len(), |offset| self.index + offset);
     }
 
     pub fn skip_whitespace_raw(&mut self) -> IndentState {
@@ -112,7 +124,7 @@ impl<'a> Parser<'a> for SliceParser<'a> {
         str::from_utf8(key).map_err(|e| unsafe {
             Error::new(
                 ErrorCode::InvalidUtf8,
-                self.position_of_ptr(key.as_ptr().add(e.valid_up_to())),
+                self.location_of_ptr(key.as_ptr().add(e.valid_up_to())),
             )
         })
     }
@@ -122,7 +134,17 @@ impl<'a> Parser<'a> for SliceParser<'a> {
 
         str::from_utf8(value).map_err(|e| {
             Error::new(ErrorCode::InvalidUtf8, unsafe {
-                self.position_of_ptr(value.as_ptr().add(e.valid_up_to()))
+                self.location_of_ptr(value.as_ptr().add(e.valid_up_to()))
+            })
+        })
+    }
+
+    fn parse_remaining(&mut self) -> Result<&'a str> {
+        let remaining = self.parse_remaining_raw();
+
+        str::from_utf8(remaining).map_err(|e| {
+            Error::new(ErrorCode::InvalidUtf8, unsafe {
+                self.location_of_ptr(remaining.as_ptr().add(e.valid_up_to()))
             })
         })
     }
@@ -135,10 +157,26 @@ impl<'a> Parser<'a> for SliceParser<'a> {
         self.data
     }
 
+    fn line_index(&self) -> &LineIndex<'a> {
+        self.line_index.get_or_init(|| LineIndex::new(self.data))
+    }
+
+    fn index(&self) -> usize {
+        self.index
+    }
+
     fn last_key_index(&self) -> usize {
         self.last_key_index
     }
 
+    fn last_key_end(&self) -> usize {
+        self.last_key_end
+    }
+
+    fn last_value_end(&self) -> usize {
+        self.last_value_end
+    }
+
     fn last_key_indent(&self) -> u32 {
         self.last_key_indent
     }
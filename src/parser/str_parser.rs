@@ -1,4 +1,5 @@
 use crate::error::Result;
+use crate::line_index::LineIndex;
 use crate::parser::{IndentState, Parser, SliceParser};
 use core::str;
 
@@ -27,6 +28,11 @@ impl<'a> Parser<'a> for StrParser<'a> {
         unsafe { Ok(str::from_utf8_unchecked(value)) }
     }
 
+    fn parse_remaining(&mut self) -> Result<&'a str> {
+        let remaining = self.delegate.parse_remaining_raw();
+        unsafe { Ok(str::from_utf8_unchecked(remaining)) }
+    }
+
     fn skip_whitespace(&mut self) -> Result<IndentState> {
         Ok(self.delegate.skip_whitespace_raw())
     }
@@ -35,10 +41,26 @@ impl<'a> Parser<'a> for StrParser<'a> {
         self.delegate.data()
     }
 
+    fn line_index(&self) -> &LineIndex<'a> {
+        self.delegate.line_index()
+    }
+
+    fn index(&self) -> usize {
+        self.delegate.index()
+    }
+
     fn last_key_index(&self) -> usize {
         self.delegate.last_key_index()
     }
 
+    fn last_key_end(&self) -> usize {
+        self.delegate.last_key_end()
+    }
+
+    fn last_value_end(&self) -> usize {
+        self.delegate.last_value_end()
+    }
+
     fn last_key_indent(&self) -> u32 {
         self.delegate.last_key_indent()
     }
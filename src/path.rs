@@ -0,0 +1,327 @@
+use crate::document::{from_entry_value, Document, Entry, Value};
+use crate::error::{Error, ErrorCode, Result};
+use crate::position::Location;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+use serde_core::de::DeserializeOwned;
+
+/// Path to an entry in a [`Document`].
+///
+/// Paths are written as keys separated by `.`, where each key may be followed
+/// by list item indices in brackets, such as `servers[0].port`. Empty
+/// brackets, as in `hosts[]`, refer to a new item appended to the list.
+///
+/// ```
+/// use serde_ccl::{KeyPath, Segment};
+///
+/// let path = "servers[1].hosts[]".parse::<KeyPath>().unwrap();
+///
+/// assert_eq!(
+///     path.segments(),
+///     [
+///         Segment::Key("servers".into()),
+///         Segment::Index(1),
+///         Segment::Key("hosts".into()),
+///         Segment::Append,
+///     ],
+/// );
+/// assert_eq!(path.to_string(), "servers[1].hosts[]");
+/// ```
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug)]
+pub struct KeyPath {
+    segments: Vec<Segment>,
+}
+
+/// Segment of a [`KeyPath`].
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Segment {
+    /// Last entry with the given key.
+    Key(String),
+
+    /// List item at the given index, counting only entries with empty keys.
+    Index(usize),
+
+    /// New list item appended to the list.
+    Append,
+}
+
+impl KeyPath {
+    /// Parses a key path.
+    ///
+    /// Errors are located at the invalid character on line 1.
+    pub fn parse(path: &str) -> Result<Self> {
+        let error = |offset: usize| Error::new(ErrorCode::InvalidPath, location(&path[..offset]));
+
+        let mut segments = Vec::new();
+        let mut index = 0;
+
+        while index < path.len() {
+            let rest = &path[index..];
+
+            if let Some(rest) = rest.strip_prefix('[') {
+                let len = rest.find(']').ok_or_else(|| error(index))?;
+
+                segments.push(match &rest[..len] {
+                    "" => Segment::Append,
+                    digits => Segment::Index(digits.parse().map_err(|_| error(index + 1))?),
+                });
+
+                index += len + 2;
+
+                match path[index..].chars().next() {
+                    None | Some('[') => (),
+                    Some('.') if index + 1 < path.len() => index += 1,
+                    Some(_) => return Err(error(index)),
+                }
+
+                continue;
+            }
+
+            let len = rest.find(['.', '[', ']']).unwrap_or(rest.len());
+
+            if len == 0 {
+                return Err(error(index));
+            }
+
+            segments.push(Segment::Key(rest[..len].into()));
+            index += len;
+
+            match path[index..].chars().next() {
+                None | Some('[') => (),
+                Some('.') if index + 1 < path.len() => index += 1,
+                Some(_) => return Err(error(index)),
+            }
+        }
+
+        if segments.is_empty() {
+            return Err(error(0));
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Returns the segments of the path.
+    #[inline]
+    #[must_use]
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+}
+
+impl FromStr for KeyPath {
+    type Err = Error;
+
+    fn from_str(path: &str) -> Result<Self> {
+        Self::parse(path)
+    }
+}
+
+impl FromIterator<Segment> for KeyPath {
+    fn from_iter<I>(segments: I) -> Self
+    where
+        I: IntoIterator<Item = Segment>,
+    {
+        Self {
+            segments: segments.into_iter().collect(),
+        }
+    }
+}
+
+impl fmt::Display for KeyPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Key(key) if i == 0 => f.write_str(key)?,
+                Segment::Key(key) => write!(f, ".{key}")?,
+                Segment::Index(index) => write!(f, "[{index}]")?,
+                Segment::Append => f.write_str("[]")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Deserializes the value at the given path of the document, without
+/// deserializing the rest of the document.
+///
+/// Returns `None` if there is no entry at the path, as described in
+/// [`Document::get_path`]. Errors are located in the input the entry was
+/// parsed from.
+///
+/// ```
+/// use serde::Deserialize;
+/// use serde_ccl::Document;
+///
+/// #[derive(Deserialize)]
+/// struct Database {
+///     host: String,
+///     ports: Vec<u16>,
+/// }
+///
+/// let document = Document::parse("database =\n  host = localhost\n  ports =\n    = 5432\n").unwrap();
+///
+/// let port = serde_ccl::get::<u16>(&document, &"database.ports.0".parse().unwrap()).unwrap();
+/// assert_eq!(port, Some(5432));
+///
+/// let database = serde_ccl::get::<Database>(&document, &"database".parse().unwrap()).unwrap();
+/// assert_eq!(database.unwrap().host, "localhost");
+/// ```
+pub fn get<T>(document: &Document, path: &KeyPath) -> Result<Option<T>>
+where
+    T: DeserializeOwned,
+{
+    document.get_path(path).map(from_entry_value).transpose()
+}
+
+/// Deserializes all values at the given path of the document, as described
+/// in [`Document::get_all_path`].
+pub fn get_all<T>(document: &Document, path: &KeyPath) -> Result<Vec<T>>
+where
+    T: DeserializeOwned,
+{
+    get_all_path(document, path)
+        .into_iter()
+        .map(from_entry_value)
+        .collect()
+}
+
+/// Returns the entries at the given path.
+///
+/// Every segment but the last one resolves to a single entry, as in
+/// [`find_entry`]. If the last segment is a key, all entries with that key
+/// are returned.
+pub(crate) fn get_all_path<'a>(document: &'a Document, path: &KeyPath) -> Vec<&'a Entry> {
+    let Some((last, segments)) = path.segments.split_last() else {
+        return Vec::new();
+    };
+
+    let mut document = document;
+
+    for segment in segments {
+        let block = find_entry(document, segment).and_then(|entry| entry.value().as_block());
+
+        let Some(block) = block else {
+            return Vec::new();
+        };
+
+        document = block;
+    }
+
+    match last {
+        Segment::Key(key) if document.get(key).is_some() => {
+            document.iter().filter(|entry| entry.key() == key).collect()
+        }
+        segment => find_entry(document, segment).into_iter().collect(),
+    }
+}
+
+/// Returns the entry the segment refers to in the document.
+///
+/// Keys refer to the last entry with that key. Keys that are numbers and that
+/// no entry has refer to list items, so `ports.1` is the same as `ports[1]`.
+pub(crate) fn find_entry<'a>(document: &'a Document, segment: &Segment) -> Option<&'a Entry> {
+    find_position(document, segment).map(|position| &document.entries()[position])
+}
+
+/// Returns the position of the entry the segment refers to, as described in
+/// [`find_entry`].
+fn find_position(document: &Document, segment: &Segment) -> Option<usize> {
+    match segment {
+        Segment::Key(key) => document
+            .iter()
+            .rposition(|entry| entry.key() == key)
+            .or_else(|| find_item(document, key.parse().ok()?)),
+        Segment::Index(index) => find_item(document, *index),
+        Segment::Append => None,
+    }
+}
+
+fn find_item(document: &Document, index: usize) -> Option<usize> {
+    document
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| entry.key().is_empty())
+        .nth(index)
+        .map(|(position, _)| position)
+}
+
+/// Returns the index of the list item a segment that [`find_entry`] found
+/// nothing for refers to. Keys that are numbers only refer to list items if
+/// the document has any.
+pub(crate) fn missing_item(document: &Document, segment: &Segment) -> Option<usize> {
+    match segment {
+        Segment::Key(key) if document.iter().any(|entry| entry.key().is_empty()) => {
+            key.parse().ok()
+        }
+        Segment::Index(index) => Some(*index),
+        _ => None,
+    }
+}
+
+pub(crate) fn set_path<'a>(
+    document: &'a mut Document,
+    path: &KeyPath,
+    value: Value,
+) -> Result<&'a mut Entry> {
+    let (last, segments) = path
+        .segments
+        .split_last()
+        .ok_or_else(|| Error::new(ErrorCode::InvalidPath, Location::default()))?;
+
+    let mut document = document;
+
+    for segment in segments {
+        let entry = entry_or_insert(document, segment)?;
+
+        if entry.value().as_block().is_none() {
+            *entry.value_mut() = Value::Block(Document::new());
+        }
+
+        document = entry.value_mut().as_block_mut().unwrap();
+    }
+
+    let entry = entry_or_insert(document, last)?;
+    *entry.value_mut() = value;
+    Ok(entry)
+}
+
+/// Returns the entry the segment refers to, as in [`find_entry`], or appends
+/// a new one. Numbers that no entry has as its key refer to list items if the
+/// document has any, and are an error if the item does not exist.
+fn entry_or_insert<'a>(document: &'a mut Document, segment: &Segment) -> Result<&'a mut Entry> {
+    if let Some(position) = find_position(document, segment) {
+        return Ok(&mut document.entries_mut()[position]);
+    }
+
+    if let Some(index) = missing_item(document, segment) {
+        return Err(Error::new(
+            ErrorCode::IndexOutOfBounds(index),
+            Location::default(),
+        ));
+    }
+
+    let key = match segment {
+        Segment::Key(key) => key.as_str(),
+        _ => "",
+    };
+
+    document.push(Entry::new(key, ""));
+    Ok(document.entries_mut().last_mut().unwrap())
+}
+
+/// Returns the location after the given text on line 1.
+#[must_use]
+pub(crate) fn location(prefix: &str) -> Location {
+    let start = Location {
+        index: 0,
+        line: 1,
+        column: 1,
+        column_chars: 1,
+        column_utf16: 1,
+    };
+
+    start.advance(prefix)
+}
@@ -1,13 +1,78 @@
-#[derive(Clone, Copy, Default, Debug)]
-pub(crate) struct Position {
+use crate::line_index::ColumnMode;
+
+/// Line and column of a byte in a document, both starting at 1.
+///
+/// The unit of the column depends on the [`ColumnMode`](crate::ColumnMode) used
+/// to compute the position.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug)]
+pub struct Position {
+    /// Line number, starting at 1.
     pub line: usize,
+
+    /// Column number, starting at 1.
     pub column: usize,
 }
 
 impl Position {
+    /// Creates a new position from the given line and column.
+    #[inline]
+    #[must_use]
+    pub const fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+}
+
+/// Location of a byte in a document, with the column measured in every
+/// [`ColumnMode`](crate::ColumnMode).
+#[derive(Clone, Copy, Default, Debug)]
+pub(crate) struct Location {
+    pub index: usize,
+    pub line: usize,
+    pub column: usize,
+    pub column_chars: usize,
+    pub column_utf16: usize,
+}
+
+impl Location {
     #[inline]
     #[must_use]
     pub fn is_default(&self) -> bool {
         self.line == 0 && self.column == 0
     }
+
+    /// Returns the location after the given text, which starts at this
+    /// location.
+    #[must_use]
+    pub fn advance(&self, text: &str) -> Self {
+        match text.rfind('\n') {
+            None => Self {
+                index: self.index + text.len(),
+                line: self.line,
+                column: self.column + text.len(),
+                column_chars: self.column_chars + text.chars().count(),
+                column_utf16: self.column_utf16 + text.encode_utf16().count(),
+            },
+            Some(line_start) => {
+                let line = &text[line_start + 1..];
+
+                Self {
+                    index: self.index + text.len(),
+                    line: self.line + text.matches('\n').count(),
+                    column: 1 + line.len(),
+                    column_chars: 1 + line.chars().count(),
+                    column_utf16: 1 + line.encode_utf16().count(),
+                }
+            }
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn column(&self, mode: ColumnMode) -> usize {
+        match mode {
+            ColumnMode::Byte => self.column,
+            ColumnMode::Char => self.column_chars,
+            ColumnMode::Utf16 => self.column_utf16,
+        }
+    }
 }
@@ -0,0 +1,168 @@
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::fmt;
+use serde_core::{de, ser};
+
+pub(crate) const TOKEN: &str = "$__serde_ccl_private_RawCcl";
+
+/// Unparsed slice of a CCL document.
+///
+/// Deserializing a `&RawCcl` borrows the value of an entry without parsing it.
+/// Values that start on a new line keep the indentation of their first line, so
+/// nested entries can be deserialized later with [`RawCcl::parse`], for example
+/// once the type of the value is known.
+///
+/// `RawCcl` can only be deserialized by this crate.
+///
+/// ```
+/// use serde::Deserialize;
+/// use serde_ccl::RawCcl;
+///
+/// const CCL: &str = r"
+/// kind = http
+/// options =
+///     port = 8080
+/// ";
+///
+/// #[derive(Deserialize)]
+/// struct Plugin<'a> {
+///     kind: &'a str,
+///     options: &'a RawCcl,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct HttpOptions {
+///     port: u16,
+/// }
+///
+/// let plugin = serde_ccl::from_str::<Plugin>(CCL).unwrap();
+/// assert_eq!(plugin.kind, "http");
+///
+/// let options = plugin.options.parse::<HttpOptions>().unwrap();
+/// assert_eq!(options.port, 8080);
+/// ```
+#[repr(transparent)]
+pub struct RawCcl {
+    ccl: str,
+}
+
+impl RawCcl {
+    /// Wraps a string containing CCL without parsing it.
+    #[must_use]
+    pub fn new(ccl: &str) -> &Self {
+        // SAFETY: `RawCcl` is a transparent wrapper around `str`.
+        unsafe { &*(core::ptr::from_ref::<str>(ccl) as *const Self) }
+    }
+
+    /// Wraps an owned string containing CCL without parsing it.
+    #[must_use]
+    pub fn from_string(ccl: String) -> Box<Self> {
+        let ccl = Box::into_raw(ccl.into_boxed_str());
+
+        // SAFETY: `RawCcl` is a transparent wrapper around `str`.
+        unsafe { Box::from_raw(ccl as *mut Self) }
+    }
+
+    /// Returns the unparsed CCL.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> &str {
+        &self.ccl
+    }
+
+    /// Deserializes the value from the unparsed CCL.
+    pub fn parse<'a, T>(&'a self) -> crate::Result<T>
+    where
+        T: de::Deserialize<'a>,
+    {
+        crate::from_str(&self.ccl)
+    }
+}
+
+impl Clone for Box<RawCcl> {
+    fn clone(&self) -> Self {
+        RawCcl::from_string(self.ccl.into())
+    }
+}
+
+impl ToOwned for RawCcl {
+    type Owned = Box<RawCcl>;
+
+    fn to_owned(&self) -> Self::Owned {
+        RawCcl::from_string(self.ccl.into())
+    }
+}
+
+impl fmt::Debug for RawCcl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("RawCcl").field(&&self.ccl).finish()
+    }
+}
+
+impl fmt::Display for RawCcl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.ccl)
+    }
+}
+
+impl ser::Serialize for RawCcl {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_newtype_struct(TOKEN, &self.ccl)
+    }
+}
+
+impl<'de: 'a, 'a> de::Deserialize<'de> for &'a RawCcl {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct RawVisitor;
+
+        impl<'de> de::Visitor<'de> for RawVisitor {
+            type Value = &'de RawCcl;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a borrowed CCL value")
+            }
+
+            fn visit_borrowed_str<E>(self, value: &'de str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(RawCcl::new(value))
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(TOKEN, RawVisitor)
+    }
+}
+
+impl<'de> de::Deserialize<'de> for Box<RawCcl> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct BoxedRawVisitor;
+
+        impl de::Visitor<'_> for BoxedRawVisitor {
+            type Value = Box<RawCcl>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a CCL value")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(RawCcl::from_string(value.into()))
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(TOKEN, BoxedRawVisitor)
+    }
+}
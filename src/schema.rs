@@ -0,0 +1,697 @@
+use crate::document::{Document, Entry, Value};
+use crate::error::{Error, ErrorCode, Result};
+use crate::path::{location, KeyPath, Segment};
+use crate::position::Location;
+use crate::spanned::Span;
+use regex_lite::Regex;
+use serde_json::{Map, Value as Json};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// JSON Schema that CCL documents are validated against.
+///
+/// The following subset of JSON Schema is supported, and other keywords are
+/// ignored:
+///
+/// - `type`, `enum` and `const`.
+/// - `properties`, `required` and `additionalProperties` for maps.
+/// - `items`, `minItems` and `maxItems` for lists.
+/// - `minimum`, `maximum`, `exclusiveMinimum` and `exclusiveMaximum` for
+///   numbers.
+/// - `minLength`, `maxLength` and `pattern` for strings.
+/// - `allOf`, `anyOf` and `oneOf`.
+/// - `$ref` to a location in the same schema, such as `#/$defs/Server`.
+///
+/// All values in a CCL document are text, so the types of values are decided
+/// by what they parse as: `integer` and `number` values parse as numbers,
+/// `boolean` values are `true` or `false` and `null` values are empty. Blocks
+/// are `array`s when all their entries are list items and `object`s otherwise.
+/// Empty values are also empty `object`s, `array`s and `string`s. Comments are
+/// ignored.
+///
+/// ```
+/// use serde_ccl::{Document, Schema};
+///
+/// let schema = Schema::new(serde_json::json!({
+///     "type": "object",
+///     "required": ["name", "port"],
+///     "properties": {
+///         "name": { "type": "string", "pattern": "^[a-z]+$" },
+///         "port": { "type": "integer", "minimum": 1, "maximum": 65535 },
+///     },
+/// }))
+/// .unwrap();
+///
+/// let document = Document::parse("name = App\nport = 0\n").unwrap();
+/// let violations = schema.validate(&document);
+///
+/// assert_eq!(
+///     violations.iter().map(ToString::to_string).collect::<Vec<_>>(),
+///     [
+///         "name: does not match the pattern `^[a-z]+$` at line 1 column 8",
+///         "port: must be at least 1 at line 2 column 8",
+///     ],
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Schema {
+    root: Json,
+    patterns: BTreeMap<String, Regex>,
+}
+
+impl Schema {
+    /// Creates a schema from its JSON representation.
+    ///
+    /// Fails if a pattern is not a valid regular expression or a `$ref` does
+    /// not refer to a location in the schema.
+    pub fn new(root: Json) -> Result<Self> {
+        let mut patterns = BTreeMap::new();
+        check(&root, &root, &mut patterns)?;
+        Ok(Self { root, patterns })
+    }
+
+    /// Generates the schema of a type with [`schemars`], describing how its
+    /// values are written in CCL.
+    ///
+    /// The generated schema follows the mapping used when deserializing:
+    ///
+    /// - Sequences are blocks of list items, which are entries with empty
+    ///   keys, such as `= 80`. Their schemas are marked with `"x-ccl": "list"`.
+    /// - Enum variants with data are blocks with a single entry whose key is
+    ///   the name of the variant, such as `Slow =` followed by the data. Their
+    ///   schemas are marked with `"x-ccl": "variant"`.
+    /// - Unit variants are blocks with a single entry whose key is the name of
+    ///   the variant and whose value is empty, such as `Fast =`. Their schemas
+    ///   are rewritten from strings to such blocks and marked with
+    ///   `"x-ccl": "variant"`.
+    /// - `None` and the unit type are empty values. Their schemas are marked
+    ///   with `"x-ccl": "empty"`.
+    ///
+    /// Each marked schema also gets a description of how to write its values,
+    /// after the documentation of the type, if any, so editors show it.
+    ///
+    /// Fails if a pattern is not supported, like in [`Schema::new`].
+    ///
+    /// ```
+    /// use schemars::JsonSchema;
+    /// use serde_ccl::{Document, Schema};
+    ///
+    /// #[derive(JsonSchema)]
+    /// struct Config {
+    ///     ports: Vec<u16>,
+    /// }
+    ///
+    /// let schema = Schema::for_type::<Config>().unwrap();
+    /// assert_eq!(schema.as_json()["properties"]["ports"]["x-ccl"], "list");
+    ///
+    /// let document = Document::parse("ports =\n  = 80\n  = http\n").unwrap();
+    /// let violations = schema.validate(&document);
+    /// assert_eq!(violations[0].to_string(), "ports[1]: expected integer, found `http` at line 3 column 5");
+    /// ```
+    #[cfg(feature = "schemars")]
+    pub fn for_type<T>() -> Result<Self>
+    where
+        T: schemars::JsonSchema + ?Sized,
+    {
+        let mut root = schemars::SchemaGenerator::default()
+            .into_root_schema_for::<T>()
+            .to_value();
+
+        annotate(&mut root, false);
+        Self::new(root)
+    }
+
+    /// Returns the JSON representation of the schema.
+    #[inline]
+    #[must_use]
+    pub fn as_json(&self) -> &Json {
+        &self.root
+    }
+
+    /// Validates the document, returning the violations found ordered by
+    /// position.
+    #[must_use]
+    pub fn validate(&self, document: &Document) -> Vec<Violation> {
+        let mut validator = Validator {
+            schema: self,
+            path: Vec::new(),
+            violations: Vec::new(),
+            references: Vec::new(),
+        };
+
+        let span = Span::new(location(""), 0);
+        validator.validate(&self.root, Node::Block(document), span);
+
+        let mut violations = validator.violations;
+        violations.sort_by_key(|violation| violation.span.start());
+        violations
+    }
+}
+
+/// Value of a document that does not conform to a [`Schema`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Violation {
+    path: KeyPath,
+    message: String,
+    span: Span,
+}
+
+impl Violation {
+    /// Returns the path of the value, which is empty for the document itself.
+    #[inline]
+    #[must_use]
+    pub fn path(&self) -> &KeyPath {
+        &self.path
+    }
+
+    /// Returns the description of the violation.
+    #[inline]
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the span of the value, or of the key for unknown keys. Missing
+    /// keys are reported at the map that lacks them.
+    #[inline]
+    #[must_use]
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.path.segments().is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+
+        write!(
+            f,
+            "{} at line {} column {}",
+            self.message,
+            self.span.line(),
+            self.span.column(),
+        )
+    }
+}
+
+/// Checks the patterns and references of the schema, compiling the patterns.
+fn check(schema: &Json, root: &Json, patterns: &mut BTreeMap<String, Regex>) -> Result<()> {
+    let invalid =
+        |message: String| Error::new(ErrorCode::InvalidSchema(message), Location::default());
+
+    match schema {
+        Json::Object(object) => {
+            if let Some(Json::String(pattern)) = object.get("pattern") {
+                let regex = Regex::new(pattern)
+                    .map_err(|e| invalid(format!("invalid pattern `{pattern}`: {e}")))?;
+
+                patterns.insert(pattern.clone(), regex);
+            }
+
+            if let Some(Json::String(reference)) = object.get("$ref") {
+                if resolve(root, reference).is_none() {
+                    return Err(invalid(format!("unresolved reference `{reference}`")));
+                }
+            }
+
+            for (key, value) in object {
+                match (key.as_str(), value) {
+                    ("enum" | "const" | "default" | "examples", _) => (),
+                    (
+                        "properties" | "patternProperties" | "$defs" | "definitions",
+                        Json::Object(map),
+                    ) => {
+                        for schema in map.values() {
+                            check(schema, root, patterns)?;
+                        }
+                    }
+                    (_, value) => check(value, root, patterns)?,
+                }
+            }
+        }
+        Json::Array(values) => {
+            for value in values {
+                check(value, root, patterns)?;
+            }
+        }
+        _ => (),
+    }
+
+    Ok(())
+}
+
+/// Marks the schemas whose values are written differently in CCL than in
+/// JSON and describes how to write them. Enum variants are only recognized
+/// among the alternatives of `oneOf` and `anyOf`.
+#[cfg(feature = "schemars")]
+fn annotate(schema: &mut Json, is_alternative: bool) {
+    let Json::Object(object) = schema else {
+        return;
+    };
+
+    unit_variants(object, is_alternative);
+
+    for (key, value) in object.iter_mut() {
+        match (key.as_str(), value) {
+            ("enum" | "const" | "default" | "examples", _) => (),
+            ("properties" | "patternProperties" | "$defs" | "definitions", Json::Object(map)) => {
+                for schema in map.values_mut() {
+                    annotate(schema, false);
+                }
+            }
+            ("oneOf" | "anyOf", Json::Array(alternatives)) => {
+                for alternative in alternatives {
+                    annotate(alternative, true);
+                }
+            }
+            (_, Json::Array(schemas)) => {
+                for schema in schemas {
+                    annotate(schema, false);
+                }
+            }
+            (_, value) => annotate(value, false),
+        }
+    }
+
+    let has_type = |ty: &str| match object.get("type") {
+        Some(Json::String(types)) => types == ty,
+        Some(Json::Array(types)) => types.iter().any(|types| types == ty),
+        _ => false,
+    };
+
+    let (kind, description) = if has_type("array") {
+        (
+            "list",
+            "Written as list items, which are entries with empty keys, such as `= value`.".into(),
+        )
+    } else if let Some(variant) = variant(object).filter(|_| is_alternative) {
+        (
+            "variant",
+            format!("Written as the `{variant}` variant, an entry with the key `{variant}`."),
+        )
+    } else if has_type("null") && !has_type("string") {
+        ("empty", "Written as an empty value.".into())
+    } else {
+        return;
+    };
+
+    let description = match object.get("description").and_then(Json::as_str) {
+        Some(docs) => format!("{docs}\n\n{description}"),
+        None => description,
+    };
+
+    object.insert("x-ccl".into(), kind.into());
+    object.insert("description".into(), description.into());
+}
+
+/// Rewrites the schemas of unit variants, which [`schemars`] describes as
+/// strings, as entries whose key is the name of the variant and whose value
+/// is empty, like they are serialized.
+///
+/// Unit variants are strings listed by `enum`, or a `const` string among the
+/// alternatives of `oneOf` and `anyOf`.
+#[cfg(feature = "schemars")]
+fn unit_variants(object: &mut Map<String, Json>, is_alternative: bool) {
+    if object.get("type").and_then(Json::as_str) != Some("string") {
+        return;
+    }
+
+    let names = match (object.get("enum"), object.get("const")) {
+        (Some(Json::Array(names)), None) => names
+            .iter()
+            .map(|name| name.as_str().map(String::from))
+            .collect::<Option<Vec<_>>>(),
+        (None, Some(Json::String(name))) if is_alternative => Some(vec![name.clone()]),
+        _ => None,
+    };
+
+    let Some(names) = names.filter(|names| !names.is_empty()) else {
+        return;
+    };
+
+    object.remove("type");
+    object.remove("enum");
+    object.remove("const");
+
+    match names.as_slice() {
+        [name] if is_alternative => object.extend(unit_variant(name)),
+        _ => {
+            let alternatives = names.iter().map(|name| Json::Object(unit_variant(name)));
+            object.insert("oneOf".into(), Json::Array(alternatives.collect()));
+        }
+    }
+}
+
+/// Returns the schema of a unit variant, a map with the name of the variant as
+/// its only key and an empty value.
+#[cfg(feature = "schemars")]
+fn unit_variant(name: &str) -> Map<String, Json> {
+    let mut properties = Map::new();
+    properties.insert(name.into(), serde_json::json!({ "type": "null" }));
+
+    let mut object = Map::new();
+    object.insert("type".into(), "object".into());
+    object.insert("properties".into(), properties.into());
+    object.insert("required".into(), Json::Array(vec![name.into()]));
+    object.insert("additionalProperties".into(), false.into());
+    object
+}
+
+/// Returns the name of the enum variant the schema describes, if it describes
+/// a map with a single required key and no other keys.
+#[cfg(feature = "schemars")]
+fn variant(object: &Map<String, Json>) -> Option<&str> {
+    let properties = object.get("properties")?.as_object()?;
+
+    if properties.len() != 1 || object.get("additionalProperties") != Some(&Json::Bool(false)) {
+        return None;
+    }
+
+    let (name, _) = properties.iter().next()?;
+    let required = object.get("required")?.as_array()?;
+    (required.len() == 1 && required[0] == **name).then_some(name.as_str())
+}
+
+/// Returns the subschema a `$ref` refers to.
+pub(crate) fn resolve<'a>(root: &'a Json, reference: &str) -> Option<&'a Json> {
+    let pointer = reference.strip_prefix('#')?;
+    root.pointer(&pointer.replace("%24", "$"))
+}
+
+/// Value of a document, or the document itself.
+#[derive(Clone, Copy)]
+enum Node<'a> {
+    Text(&'a str),
+    Block(&'a Document),
+}
+
+impl<'a> Node<'a> {
+    fn of(entry: &'a Entry) -> Self {
+        match entry.value() {
+            Value::Text(text) => Node::Text(text),
+            Value::Block(block) => Node::Block(block),
+        }
+    }
+
+    fn is_type(self, ty: &str) -> bool {
+        match (self, ty) {
+            (Node::Text(_), "string") => true,
+            (Node::Text(text), "integer") => {
+                text.parse::<i64>().is_ok() || text.parse::<u64>().is_ok()
+            }
+            (Node::Text(text), "number") => text.parse::<f64>().is_ok(),
+            (Node::Text(text), "boolean") => text.parse::<bool>().is_ok(),
+            (Node::Text(text), "null" | "object" | "array") => text.is_empty(),
+            (Node::Block(block), "object") => !block.is_list(),
+            (Node::Block(block), "array") => block.is_list(),
+            _ => false,
+        }
+    }
+
+    fn describe(self) -> String {
+        match self {
+            Node::Text(text) => format!("`{text}`"),
+            Node::Block(block) if block.is_list() => "a list".into(),
+            Node::Block(_) => "a map".into(),
+        }
+    }
+}
+
+struct Validator<'a> {
+    schema: &'a Schema,
+    path: Vec<Segment>,
+    violations: Vec<Violation>,
+
+    /// Schemas referred to by the `$ref`s followed for the current node.
+    references: Vec<&'a Json>,
+}
+
+impl<'a> Validator<'a> {
+    fn report(&mut self, message: String, span: Span) {
+        self.violations.push(Violation {
+            path: self.path.iter().cloned().collect(),
+            message,
+            span,
+        });
+    }
+
+    /// Returns whether the node conforms to the schema, without reporting
+    /// violations.
+    fn conforms(&self, schema: &'a Json, node: Node<'_>, span: Span) -> bool {
+        let mut validator = Validator {
+            schema: self.schema,
+            path: self.path.clone(),
+            violations: Vec::new(),
+            references: self.references.clone(),
+        };
+
+        validator.validate(schema, node, span);
+        validator.violations.is_empty()
+    }
+
+    /// Validates the node, whose value starts at the given span.
+    fn validate(&mut self, schema: &'a Json, node: Node<'_>, span: Span) {
+        let object = match schema {
+            Json::Bool(false) => return self.report("is not allowed".into(), span),
+            Json::Object(object) => object,
+            _ => return,
+        };
+
+        if let Some(Json::String(reference)) = object.get("$ref") {
+            if let Some(schema) = resolve(&self.schema.root, reference) {
+                if self
+                    .references
+                    .iter()
+                    .any(|seen| core::ptr::eq(*seen, schema))
+                {
+                    return self.report(format!("reference cycle at `{reference}`"), span);
+                }
+
+                self.references.push(schema);
+                self.validate(schema, node, span);
+                self.references.pop();
+            }
+        }
+
+        if let Some(types) = object.get("type") {
+            let types = match types {
+                Json::Array(types) => types.iter().filter_map(Json::as_str).collect(),
+                types => types.as_str().into_iter().collect::<Vec<_>>(),
+            };
+
+            if !types.iter().any(|ty| node.is_type(ty)) {
+                let message = format!("expected {}, found {}", types.join(" or "), node.describe());
+                return self.report(message, span);
+            }
+        }
+
+        if let Some(Json::Array(values)) = object.get("enum") {
+            if !values.iter().any(|value| equals(node, value)) {
+                let values = values.iter().map(|value| format!("`{}`", display(value)));
+                let message = format!("expected one of {}", values.collect::<Vec<_>>().join(", "));
+                self.report(message, span);
+            }
+        }
+
+        if let Some(value) = object.get("const") {
+            if !equals(node, value) {
+                self.report(format!("expected `{}`", display(value)), span);
+            }
+        }
+
+        self.validate_combinators(object, node, span);
+
+        match node {
+            // Empty values are also empty maps and lists.
+            Node::Text("") => {
+                self.validate_text(object, "", span);
+                self.validate_list(object, &Document::default(), span);
+                self.validate_map(object, &Document::default(), span);
+            }
+            Node::Text(text) => self.validate_text(object, text, span),
+            Node::Block(block) if block.is_list() => self.validate_list(object, block, span),
+            Node::Block(block) => self.validate_map(object, block, span),
+        }
+    }
+
+    /// Validates the value of an entry of the current node, which may follow
+    /// the same `$ref`s again.
+    fn validate_child(&mut self, schema: &'a Json, entry: &Entry) {
+        let references = core::mem::take(&mut self.references);
+        self.validate(schema, Node::of(entry), entry.origin().value_span());
+        self.references = references;
+    }
+
+    fn validate_combinators(&mut self, object: &'a Map<String, Json>, node: Node<'_>, span: Span) {
+        if let Some(Json::Array(schemas)) = object.get("allOf") {
+            for schema in schemas {
+                self.validate(schema, node, span);
+            }
+        }
+
+        if let Some(Json::Array(schemas)) = object.get("anyOf") {
+            if !schemas
+                .iter()
+                .any(|schema| self.conforms(schema, node, span))
+            {
+                self.report("does not match any of the allowed schemas".into(), span);
+            }
+        }
+
+        if let Some(Json::Array(schemas)) = object.get("oneOf") {
+            let count = schemas
+                .iter()
+                .filter(|schema| self.conforms(schema, node, span))
+                .count();
+
+            if count != 1 {
+                self.report(
+                    "does not match exactly one of the allowed schemas".into(),
+                    span,
+                );
+            }
+        }
+    }
+
+    fn validate_text(&mut self, object: &Map<String, Json>, text: &str, span: Span) {
+        if let Ok(number) = text.parse::<f64>() {
+            let bound = |key: &str| object.get(key).and_then(Json::as_f64);
+
+            if let Some(minimum) = bound("minimum").filter(|minimum| number < *minimum) {
+                self.report(format!("must be at least {minimum}"), span);
+            }
+
+            if let Some(maximum) = bound("maximum").filter(|maximum| number > *maximum) {
+                self.report(format!("must be at most {maximum}"), span);
+            }
+
+            if let Some(minimum) = bound("exclusiveMinimum").filter(|minimum| number <= *minimum) {
+                self.report(format!("must be greater than {minimum}"), span);
+            }
+
+            if let Some(maximum) = bound("exclusiveMaximum").filter(|maximum| number >= *maximum) {
+                self.report(format!("must be less than {maximum}"), span);
+            }
+        }
+
+        let length = text.chars().count();
+        let bound = |key: &str| object.get(key).and_then(Json::as_u64);
+
+        if let Some(min_length) = bound("minLength").filter(|min| (length as u64) < *min) {
+            self.report(
+                format!("must be at least {min_length} characters long"),
+                span,
+            );
+        }
+
+        if let Some(max_length) = bound("maxLength").filter(|max| (length as u64) > *max) {
+            self.report(
+                format!("must be at most {max_length} characters long"),
+                span,
+            );
+        }
+
+        if let Some(Json::String(pattern)) = object.get("pattern") {
+            match self.schema.patterns.get(pattern) {
+                Some(regex) if regex.is_match(text) => (),
+                Some(_) => self.report(format!("does not match the pattern `{pattern}`"), span),
+                None => self.report(format!("cannot check the pattern `{pattern}`"), span),
+            }
+        }
+    }
+
+    fn validate_list(&mut self, object: &'a Map<String, Json>, block: &Document, span: Span) {
+        let items = block.iter().filter(|entry| entry.key().is_empty());
+        let count = items.clone().count();
+        let bound = |key: &str| object.get(key).and_then(Json::as_u64);
+
+        if let Some(min_items) = bound("minItems").filter(|min| (count as u64) < *min) {
+            self.report(format!("must have at least {min_items} items"), span);
+        }
+
+        if let Some(max_items) = bound("maxItems").filter(|max| (count as u64) > *max) {
+            self.report(format!("must have at most {max_items} items"), span);
+        }
+
+        let Some(schema) = object.get("items") else {
+            return;
+        };
+
+        for (index, entry) in items.enumerate() {
+            self.path.push(Segment::Index(index));
+            self.validate_child(schema, entry);
+            self.path.pop();
+        }
+    }
+
+    fn validate_map(&mut self, object: &'a Map<String, Json>, block: &Document, span: Span) {
+        let properties = object.get("properties").and_then(Json::as_object);
+
+        if let Some(Json::Array(required)) = object.get("required") {
+            for key in required.iter().filter_map(Json::as_str) {
+                if block.get(key).is_none() {
+                    self.report(format!("missing required key `{key}`"), span);
+                }
+            }
+        }
+
+        let additional = object.get("additionalProperties");
+
+        for entry in block {
+            if entry.key() == "/" || entry.key().is_empty() {
+                continue;
+            }
+
+            // Repeated keys are validated once, at the last entry.
+            if !block
+                .get(entry.key())
+                .is_some_and(|last| core::ptr::eq(last, entry))
+            {
+                continue;
+            }
+
+            let property = properties.and_then(|properties| properties.get(entry.key()));
+
+            let Some(schema) = property.or(additional) else {
+                continue;
+            };
+
+            self.path.push(Segment::Key(entry.key().into()));
+
+            if property.is_none() && schema == &Json::Bool(false) {
+                let message = format!("unknown key `{}`", entry.key());
+                self.report(message, entry.origin().key_span());
+            } else {
+                self.validate_child(schema, entry);
+            }
+
+            self.path.pop();
+        }
+    }
+}
+
+/// Returns whether the node is equal to the JSON value, comparing numbers by
+/// value.
+fn equals(node: Node<'_>, value: &Json) -> bool {
+    let Node::Text(text) = node else {
+        return false;
+    };
+
+    match value {
+        Json::Null => text.is_empty(),
+        Json::Bool(value) => text.parse() == Ok(*value),
+        Json::Number(number) => text.parse::<f64>().ok() == number.as_f64(),
+        Json::String(value) => text == value,
+        _ => false,
+    }
+}
+
+fn display(value: &Json) -> String {
+    match value {
+        Json::String(value) => value.clone(),
+        Json::Null => String::new(),
+        value => value.to_string(),
+    }
+}
@@ -0,0 +1,640 @@
+use crate::document::{Document, Entry, Value};
+use crate::error::{Error, ErrorCode, Result};
+use crate::position::Location;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Display;
+use serde_core::ser::{self, Serialize};
+
+/// Serializes the value as a CCL string.
+///
+/// Values are written with the same conventions [`from_str`](crate::from_str)
+/// reads them with:
+///
+/// - Scalars are written as text, and `None` and `()` as empty values.
+/// - Structs and maps are written as nested entries, with keys written as
+///   text. Empty keys, `/` and keys containing `=` or newlines cannot be
+///   written, as they would be read back as list items, comments or other
+///   keys.
+/// - Sequences and tuples are written as nested entries with empty keys.
+/// - Enum variants are written as a nested entry whose key is the variant name
+///   and whose value is the payload of the variant, if any.
+///
+/// Text is written without leading and trailing spaces and newlines, which
+/// would be lost when reading it back, and lines following the first line of a
+/// nested value are indented further when they are not indented deeper than the
+/// key.
+///
+/// ```
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Config {
+///     name: String,
+///     ports: Vec<u16>,
+/// }
+///
+/// let config = Config {
+///     name: "app".into(),
+///     ports: vec![80, 443],
+/// };
+///
+/// let ccl = serde_ccl::to_string(&config).unwrap();
+/// assert_eq!(ccl, "name = app\nports =\n    = 80\n    = 443\n");
+/// ```
+pub fn to_string<T>(value: &T) -> Result<String>
+where
+    T: ?Sized + Serialize,
+{
+    match value.serialize(ValueSerializer)? {
+        Value::Text(text) if text.is_empty() => Ok(text),
+        Value::Text(mut text) => {
+            text.push('\n');
+            Ok(text)
+        }
+        Value::Block(block) => Ok(block.to_string()),
+    }
+}
+
+/// Serializes the value as a CCL document.
+///
+/// Fails if the value is not written as nested entries, as described in
+/// [`to_string`].
+pub fn to_document<T>(value: &T) -> Result<Document>
+where
+    T: ?Sized + Serialize,
+{
+    match value.serialize(ValueSerializer)? {
+        Value::Block(block) => Ok(block),
+        Value::Text(_) => Err(Error::new(ErrorCode::ExpectedBlock, Location::default())),
+    }
+}
+
+/// Serializes the value of an entry.
+struct ValueSerializer;
+
+impl ValueSerializer {
+    fn text<T>(value: &T) -> Value
+    where
+        T: ?Sized + Display,
+    {
+        let text = value.to_string();
+        Value::Text(text.trim_matches([' ', '\n']).into())
+    }
+
+    fn variant(variant: &str, value: Value) -> Value {
+        Value::Block(Document::from_iter([entry(variant, value)]))
+    }
+}
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = VariantSerializer<SeqSerializer>;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = MapSerializer;
+    type SerializeStructVariant = VariantSerializer<MapSerializer>;
+
+    fn serialize_bool(self, value: bool) -> Result<Value> {
+        Ok(Self::text(&value))
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<Value> {
+        Ok(Self::text(&value))
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<Value> {
+        Ok(Self::text(&value))
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<Value> {
+        Ok(Self::text(&value))
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<Value> {
+        Ok(Self::text(&value))
+    }
+
+    fn serialize_i128(self, value: i128) -> Result<Value> {
+        Ok(Self::text(&value))
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<Value> {
+        Ok(Self::text(&value))
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<Value> {
+        Ok(Self::text(&value))
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<Value> {
+        Ok(Self::text(&value))
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<Value> {
+        Ok(Self::text(&value))
+    }
+
+    fn serialize_u128(self, value: u128) -> Result<Value> {
+        Ok(Self::text(&value))
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<Value> {
+        Ok(Self::text(&value))
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<Value> {
+        Ok(Self::text(&value))
+    }
+
+    fn serialize_char(self, value: char) -> Result<Value> {
+        Ok(Self::text(&value))
+    }
+
+    fn serialize_str(self, value: &str) -> Result<Value> {
+        Ok(Self::text(&value))
+    }
+
+    fn collect_str<T>(self, value: &T) -> Result<Value>
+    where
+        T: ?Sized + Display,
+    {
+        Ok(Self::text(value))
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Value> {
+        let entries = value.iter().map(|byte| entry("", Self::text(byte)));
+        Ok(Value::Block(entries.collect()))
+    }
+
+    fn serialize_none(self) -> Result<Value> {
+        Ok(Value::default())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Value>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value> {
+        Ok(Value::default())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value> {
+        Ok(Value::default())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Value> {
+        Ok(Self::variant(variant, Value::default()))
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Value>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(Self::variant(variant, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer> {
+        Ok(SeqSerializer {
+            entries: Vec::with_capacity(len.unwrap_or_default()),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SeqSerializer> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<VariantSerializer<SeqSerializer>> {
+        Ok(VariantSerializer {
+            variant,
+            inner: self.serialize_seq(Some(len))?,
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<MapSerializer> {
+        Ok(MapSerializer {
+            entries: Vec::with_capacity(len.unwrap_or_default()),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<MapSerializer> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<VariantSerializer<MapSerializer>> {
+        Ok(VariantSerializer {
+            variant,
+            inner: self.serialize_map(Some(len))?,
+        })
+    }
+}
+
+/// Serializes sequences and tuples as entries with empty keys.
+struct SeqSerializer {
+    entries: Vec<Entry>,
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let value = value.serialize(ValueSerializer)?;
+        self.entries.push(entry("", value));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(Value::Block(Document::from_iter(self.entries)))
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+/// Serializes maps and structs as entries with the keys of their fields.
+struct MapSerializer {
+    entries: Vec<Entry>,
+    key: Option<String>,
+}
+
+impl MapSerializer {
+    fn push<T>(&mut self, key: String, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let value = value.serialize(ValueSerializer)?;
+        self.entries.push(entry(key, value));
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for MapSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let key = self.key.take().unwrap_or_default();
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(Value::Block(Document::from_iter(self.entries)))
+    }
+}
+
+impl ser::SerializeStruct for MapSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push(check_key(key.into())?, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        ser::SerializeMap::end(self)
+    }
+}
+
+/// Serializes the payload of a tuple or struct variant nested under the name
+/// of the variant.
+struct VariantSerializer<S> {
+    variant: &'static str,
+    inner: S,
+}
+
+impl ser::SerializeTupleVariant for VariantSerializer<SeqSerializer> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(&mut self.inner, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        let value = ser::SerializeSeq::end(self.inner)?;
+        Ok(ValueSerializer::variant(self.variant, value))
+    }
+}
+
+impl ser::SerializeStructVariant for VariantSerializer<MapSerializer> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        let value = ser::SerializeMap::end(self.inner)?;
+        Ok(ValueSerializer::variant(self.variant, value))
+    }
+}
+
+/// Creates an entry, writing empty nested entries as an empty value, which is
+/// how they are parsed.
+fn entry<K>(key: K, value: Value) -> Entry
+where
+    K: Into<String>,
+{
+    match value {
+        Value::Block(block) if block.is_empty() => Entry::new(key, Value::default()),
+        value => Entry::new(key, value),
+    }
+}
+
+/// Checks that the key is read back as is.
+fn check_key(key: String) -> Result<String> {
+    if matches!(key.as_str(), "" | "/") || key.contains(['=', '\n']) || key.trim_matches(' ') != key
+    {
+        return Err(Error::new(ErrorCode::InvalidKey(key), Location::default()));
+    }
+
+    Ok(key)
+}
+
+/// Serializes map keys, which must be scalars.
+struct KeySerializer;
+
+impl KeySerializer {
+    fn key<T>(key: &T) -> Result<String>
+    where
+        T: ?Sized + Display,
+    {
+        check_key(key.to_string())
+    }
+}
+
+fn key_must_be_scalar() -> Error {
+    Error::new(ErrorCode::KeyMustBeScalar, Location::default())
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = Error;
+
+    type SerializeSeq = ser::Impossible<String, Error>;
+    type SerializeTuple = ser::Impossible<String, Error>;
+    type SerializeTupleStruct = ser::Impossible<String, Error>;
+    type SerializeTupleVariant = ser::Impossible<String, Error>;
+    type SerializeMap = ser::Impossible<String, Error>;
+    type SerializeStruct = ser::Impossible<String, Error>;
+    type SerializeStructVariant = ser::Impossible<String, Error>;
+
+    fn serialize_bool(self, value: bool) -> Result<String> {
+        Self::key(&value)
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<String> {
+        Self::key(&value)
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<String> {
+        Self::key(&value)
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<String> {
+        Self::key(&value)
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<String> {
+        Self::key(&value)
+    }
+
+    fn serialize_i128(self, value: i128) -> Result<String> {
+        Self::key(&value)
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<String> {
+        Self::key(&value)
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<String> {
+        Self::key(&value)
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<String> {
+        Self::key(&value)
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<String> {
+        Self::key(&value)
+    }
+
+    fn serialize_u128(self, value: u128) -> Result<String> {
+        Self::key(&value)
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<String> {
+        Self::key(&value)
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<String> {
+        Self::key(&value)
+    }
+
+    fn serialize_char(self, value: char) -> Result<String> {
+        Self::key(&value)
+    }
+
+    fn serialize_str(self, value: &str) -> Result<String> {
+        Self::key(&value)
+    }
+
+    fn collect_str<T>(self, value: &T) -> Result<String>
+    where
+        T: ?Sized + Display,
+    {
+        Self::key(value)
+    }
+
+    fn serialize_bytes(self, _value: &[u8]) -> Result<String> {
+        Err(key_must_be_scalar())
+    }
+
+    fn serialize_none(self) -> Result<String> {
+        Err(key_must_be_scalar())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<String>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<String> {
+        Err(key_must_be_scalar())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String> {
+        Err(key_must_be_scalar())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String> {
+        Self::key(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<String>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(key_must_be_scalar())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(key_must_be_scalar())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(key_must_be_scalar())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(key_must_be_scalar())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(key_must_be_scalar())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(key_must_be_scalar())
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Err(key_must_be_scalar())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(key_must_be_scalar())
+    }
+}
@@ -0,0 +1,374 @@
+use crate::line_index::ColumnMode;
+use crate::position::{Location, Position};
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut, Range};
+use serde_core::{de, ser};
+
+pub(crate) const NAME: &str = "$__serde_ccl_private_Spanned";
+
+pub(crate) const FIELDS: &[&str] = &[
+    "$__serde_ccl_private_value",
+    "$__serde_ccl_private_key_start",
+    "$__serde_ccl_private_key_end",
+    "$__serde_ccl_private_key_line",
+    "$__serde_ccl_private_key_column",
+    "$__serde_ccl_private_key_column_chars",
+    "$__serde_ccl_private_key_column_utf16",
+    "$__serde_ccl_private_value_start",
+    "$__serde_ccl_private_value_end",
+    "$__serde_ccl_private_value_line",
+    "$__serde_ccl_private_value_column",
+    "$__serde_ccl_private_value_column_chars",
+    "$__serde_ccl_private_value_column_utf16",
+];
+
+/// Byte range of an element in a document, together with the line and column at
+/// which it starts.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct Span {
+    start: usize,
+    end: usize,
+    line: usize,
+    column: usize,
+    column_chars: usize,
+    column_utf16: usize,
+}
+
+impl Span {
+    #[must_use]
+    pub(crate) fn new(start: Location, end: usize) -> Self {
+        Self {
+            start: start.index,
+            end: end.max(start.index),
+            line: start.line,
+            column: start.column,
+            column_chars: start.column_chars,
+            column_utf16: start.column_utf16,
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn location(&self) -> Location {
+        Location {
+            index: self.start,
+            line: self.line,
+            column: self.column,
+            column_chars: self.column_chars,
+            column_utf16: self.column_utf16,
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn to_fields(self) -> [usize; 6] {
+        [
+            self.start,
+            self.end,
+            self.line,
+            self.column,
+            self.column_chars,
+            self.column_utf16,
+        ]
+    }
+
+    #[must_use]
+    fn from_fields(fields: &[usize]) -> Self {
+        Self {
+            start: fields[0],
+            end: fields[1],
+            line: fields[2],
+            column: fields[3],
+            column_chars: fields[4],
+            column_utf16: fields[5],
+        }
+    }
+
+    /// Returns the byte offset at which the span starts.
+    #[inline]
+    #[must_use]
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Returns the byte offset at which the span ends, exclusive.
+    #[inline]
+    #[must_use]
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Returns the byte range covered by the span.
+    #[inline]
+    #[must_use]
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Returns the line at which the span starts.
+    #[inline]
+    #[must_use]
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the column at which the span starts, measured in bytes.
+    #[inline]
+    #[must_use]
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Returns the column at which the span starts, measured in Unicode scalar
+    /// values.
+    #[inline]
+    #[must_use]
+    pub fn column_chars(&self) -> usize {
+        self.column_chars
+    }
+
+    /// Returns the column at which the span starts, measured in UTF-16 code
+    /// units.
+    #[inline]
+    #[must_use]
+    pub fn column_utf16(&self) -> usize {
+        self.column_utf16
+    }
+
+    /// Returns the position at which the span starts, with the column measured
+    /// in the given mode.
+    #[must_use]
+    pub fn position(&self, mode: ColumnMode) -> Position {
+        let column = match mode {
+            ColumnMode::Byte => self.column,
+            ColumnMode::Char => self.column_chars,
+            ColumnMode::Utf16 => self.column_utf16,
+        };
+
+        Position::new(self.line, column)
+    }
+}
+
+/// Value wrapper that records the location of the key and value it was
+/// deserialized from.
+///
+/// When used as the value of an entry, the key span covers the key of the entry
+/// and the value span covers the value of the entry, including nested entries.
+/// When used as the key of a map, both spans cover the key. When used as the
+/// top-level value, the key span is empty.
+///
+/// `Spanned` can only be deserialized by this crate. Comparing, ordering and
+/// hashing only take the inner value into account.
+///
+/// ```
+/// use serde::Deserialize;
+/// use serde_ccl::Spanned;
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     port: Spanned<u16>,
+/// }
+///
+/// let config = serde_ccl::from_str::<Config>("port = 8080").unwrap();
+/// assert_eq!(*config.port, 8080);
+/// assert_eq!(config.port.key_span().range(), 0..4);
+/// assert_eq!(config.port.value_span().range(), 7..11);
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct Spanned<T> {
+    key_span: Span,
+    value_span: Span,
+    value: T,
+}
+
+impl<T> Spanned<T> {
+    /// Creates a new spanned value from the given spans and value.
+    #[inline]
+    #[must_use]
+    pub const fn new(key_span: Span, value_span: Span, value: T) -> Self {
+        Self {
+            key_span,
+            value_span,
+            value,
+        }
+    }
+
+    /// Returns the span of the key.
+    #[inline]
+    #[must_use]
+    pub fn key_span(&self) -> Span {
+        self.key_span
+    }
+
+    /// Returns the span of the value.
+    #[inline]
+    #[must_use]
+    pub fn value_span(&self) -> Span {
+        self.value_span
+    }
+
+    /// Returns a reference to the inner value.
+    #[inline]
+    #[must_use]
+    pub fn get_ref(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns a mutable reference to the inner value.
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    /// Consumes the wrapper and returns the inner value.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Spanned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Spanned<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T> PartialEq for Spanned<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T> Eq for Spanned<T>
+where
+    T: Eq,
+{
+    // Empty
+}
+
+impl<T> PartialOrd for Spanned<T>
+where
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<T> Ord for Spanned<T>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+impl<T> Hash for Spanned<T>
+where
+    T: Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.value.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Spanned<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Spanned")
+            .field("key_span", &self.key_span.range())
+            .field("value_span", &self.value_span.range())
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl<T> ser::Serialize for Spanned<T>
+where
+    T: ser::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<'de, T> de::Deserialize<'de> for Spanned<T>
+where
+    T: de::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(NAME, FIELDS, SpannedVisitor(PhantomData))
+    }
+}
+
+struct SpannedVisitor<T>(PhantomData<T>);
+
+impl<'de, T> de::Visitor<'de> for SpannedVisitor<T>
+where
+    T: de::Deserialize<'de>,
+{
+    type Value = Spanned<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a spanned value")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        expect_field(&mut map, FIELDS[0])?;
+        let value = map.next_value()?;
+
+        let mut fields = [0; 12];
+
+        for (&name, field) in FIELDS[1..].iter().zip(&mut fields) {
+            expect_field(&mut map, name)?;
+            *field = map.next_value()?;
+        }
+
+        Ok(Spanned {
+            key_span: Span::from_fields(&fields[..6]),
+            value_span: Span::from_fields(&fields[6..]),
+            value,
+        })
+    }
+}
+
+fn expect_field<'de, A>(map: &mut A, field: &'static str) -> Result<(), A::Error>
+where
+    A: de::MapAccess<'de>,
+{
+    match map.next_key::<&str>()? {
+        Some(key) if key == field => Ok(()),
+        _ => Err(de::Error::custom(
+            "Spanned can only be deserialized by serde_ccl",
+        )),
+    }
+}
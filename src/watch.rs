@@ -0,0 +1,303 @@
+use crate::document::{from_document, Document};
+use crate::error::{Error, Result};
+use crate::include::{FileResolver, Loader};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher as _};
+use serde_core::de::DeserializeOwned;
+use std::collections::{BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
+use std::time::Duration;
+use std::{io, thread};
+
+/// Time to wait for further changes before reloading the documents.
+const DEBOUNCE: Duration = Duration::from_millis(75);
+
+/// Event delivered by a [`Watcher`] to its callback.
+#[derive(Debug)]
+pub enum WatchEvent<T> {
+    /// The watched documents changed and were deserialized into a new value.
+    Changed {
+        /// The previous value.
+        old: Arc<T>,
+
+        /// The new value.
+        new: Arc<T>,
+    },
+
+    /// The watched documents changed, but could not be loaded or deserialized.
+    /// The previous value is kept.
+    Failed(Error),
+}
+
+/// Reloads a document and the documents it includes when any of them changes.
+///
+/// The directories of the loaded documents are watched, so new documents
+/// matched by a glob include in those directories are picked up as well. Each
+/// change is reloaded and deserialized into `T` on a background thread, once
+/// no other change follows for 75 milliseconds, so that a document
+/// written in several steps is only reloaded when complete. If
+/// the loaded document differs from the previous one, the callback receives
+/// the old and the new value. If loading or deserializing fails, the callback
+/// receives the error and the last good value is kept.
+///
+/// The callback is never called concurrently. It may call back into the
+/// watcher, for example to reload it, in which case the events this raises are
+/// delivered once the callback returns.
+///
+/// The watcher stops when dropped.
+///
+/// ```no_run
+/// use serde::Deserialize;
+/// use serde_ccl::{WatchEvent, Watcher};
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     log_level: String,
+/// }
+///
+/// let watcher = Watcher::<Config>::new("/etc/app/app.ccl", |event| match event {
+///     WatchEvent::Changed { new, .. } => println!("log level: {}", new.log_level),
+///     WatchEvent::Failed(error) => eprintln!("keeping old config: {error}"),
+/// })
+/// .unwrap();
+///
+/// println!("log level: {}", watcher.current().log_level);
+/// ```
+pub struct Watcher<T> {
+    shared: Arc<Shared<T>>,
+    watcher: Arc<Mutex<RecommendedWatcher>>,
+}
+
+struct Shared<T> {
+    loader: Loader<FileResolver>,
+    path: String,
+    state: Mutex<State<T>>,
+    callback: Mutex<Callback<T>>,
+}
+
+/// Callback of a watcher and the events waiting to be delivered to it.
+struct Callback<T> {
+    /// The callback, or `None` while it is being called.
+    function: Option<Box<dyn FnMut(WatchEvent<T>) + Send>>,
+    pending: VecDeque<WatchEvent<T>>,
+}
+
+struct State<T> {
+    document: Document,
+    value: Arc<T>,
+    directories: BTreeSet<PathBuf>,
+}
+
+impl<T> Watcher<T>
+where
+    T: DeserializeOwned + Send + Sync + 'static,
+{
+    /// Loads the document at the given path with a default [`Loader`] and
+    /// starts watching it.
+    pub fn new<F>(path: &str, callback: F) -> Result<Self>
+    where
+        F: FnMut(WatchEvent<T>) + Send + 'static,
+    {
+        Self::with_loader(Loader::new(FileResolver::new()), path, callback)
+    }
+
+    /// Loads the document at the given path with the given loader and starts
+    /// watching it.
+    pub fn with_loader<F>(loader: Loader<FileResolver>, path: &str, callback: F) -> Result<Self>
+    where
+        F: FnMut(WatchEvent<T>) + Send + 'static,
+    {
+        let (document, ids) = loader.load_with_includes(path)?;
+        let value = Arc::new(from_document::<T>(&document)?);
+
+        let (sender, receiver) = mpsc::channel();
+        let watcher = notify::recommended_watcher(sender).map_err(notify_error)?;
+        let watcher = Arc::new(Mutex::new(watcher));
+
+        let shared = Arc::new(Shared {
+            loader,
+            path: path.into(),
+            state: Mutex::new(State {
+                document,
+                value,
+                directories: BTreeSet::new(),
+            }),
+            callback: Mutex::new(Callback {
+                function: Some(Box::new(callback)),
+                pending: VecDeque::new(),
+            }),
+        });
+
+        {
+            let mut state = shared.lock();
+            watch_directories(&watcher, &mut state.directories, &ids)?;
+        }
+
+        let weak_shared = Arc::downgrade(&shared);
+        let weak_watcher = Arc::downgrade(&watcher);
+        thread::spawn(move || handle_events(&receiver, &weak_shared, &weak_watcher));
+
+        Ok(Self { shared, watcher })
+    }
+
+    /// Returns the last value that was deserialized successfully.
+    #[must_use]
+    pub fn current(&self) -> Arc<T> {
+        self.shared.lock().value.clone()
+    }
+
+    /// Reloads the documents immediately, returning whether they changed.
+    ///
+    /// The callback receives the change, but not the error, which is returned
+    /// instead.
+    pub fn reload(&self) -> Result<bool> {
+        self.shared.reload(&self.watcher)
+    }
+}
+
+impl<T> Shared<T>
+where
+    T: DeserializeOwned,
+{
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Delivers the event, unless the callback is already being called, in
+    /// which case the event is delivered by that call once it returns. The
+    /// lock is not held while calling the callback, so that it can call back
+    /// into the watcher.
+    fn notify(&self, event: WatchEvent<T>) {
+        let lock = || self.callback.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let mut callback = lock();
+        callback.pending.push_back(event);
+
+        let Some(function) = callback.function.take() else {
+            return;
+        };
+
+        let mut calling = Calling {
+            callback: &self.callback,
+            function: Some(function),
+        };
+
+        while let Some(event) = callback.pending.pop_front() {
+            drop(callback);
+            (calling.function.as_mut().unwrap())(event);
+            callback = lock();
+        }
+
+        // Put back while holding the lock, so that no event is left pending.
+        callback.function = calling.function.take();
+    }
+
+    fn reload(&self, watcher: &Mutex<RecommendedWatcher>) -> Result<bool> {
+        let (document, ids) = self.loader.load_with_includes(&self.path)?;
+
+        let event = {
+            let mut state = self.lock();
+            let state = &mut *state;
+
+            watch_directories(watcher, &mut state.directories, &ids)?;
+
+            if document == state.document {
+                return Ok(false);
+            }
+
+            let new = Arc::new(from_document::<T>(&document)?);
+            let old = core::mem::replace(&mut state.value, new.clone());
+            state.document = document;
+            WatchEvent::Changed { old, new }
+        };
+
+        self.notify(event);
+        Ok(true)
+    }
+}
+
+/// Callback taken out of a watcher while it is being called, which is put
+/// back when dropped, even if the callback panics.
+struct Calling<'a, T> {
+    callback: &'a Mutex<Callback<T>>,
+    function: Option<Box<dyn FnMut(WatchEvent<T>) + Send>>,
+}
+
+impl<T> Drop for Calling<'_, T> {
+    fn drop(&mut self) {
+        if let Some(function) = self.function.take() {
+            let mut callback = self.callback.lock().unwrap_or_else(PoisonError::into_inner);
+            callback.function = Some(function);
+        }
+    }
+}
+
+fn handle_events<T>(
+    receiver: &Receiver<notify::Result<notify::Event>>,
+    shared: &Weak<Shared<T>>,
+    watcher: &Weak<Mutex<RecommendedWatcher>>,
+) where
+    T: DeserializeOwned,
+{
+    while let Ok(event) = receiver.recv() {
+        if event.is_ok_and(|event| event.kind.is_access()) {
+            continue;
+        }
+
+        // Merges the events that follow in quick succession, such as the
+        // truncation and the writes of a file that is written in place.
+        loop {
+            match receiver.recv_timeout(DEBOUNCE) {
+                Ok(_) => (),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+
+        let (Some(shared), Some(watcher)) = (shared.upgrade(), watcher.upgrade()) else {
+            break;
+        };
+
+        if let Err(error) = shared.reload(&watcher) {
+            shared.notify(WatchEvent::Failed(error));
+        }
+    }
+}
+
+/// Watches the directories of the given documents and stops watching the
+/// directories that no longer contain any of them.
+fn watch_directories(
+    watcher: &Mutex<RecommendedWatcher>,
+    directories: &mut BTreeSet<PathBuf>,
+    ids: &[String],
+) -> Result<()> {
+    let mut watcher = watcher.lock().unwrap_or_else(PoisonError::into_inner);
+
+    let new_directories = ids
+        .iter()
+        .filter_map(|id| Path::new(id).parent())
+        .map(Path::to_path_buf)
+        .collect::<BTreeSet<_>>();
+
+    for directory in directories.difference(&new_directories) {
+        // The directory may have been removed, which stops watching it.
+        let _ = watcher.unwatch(directory);
+    }
+
+    for directory in new_directories.difference(directories) {
+        watcher
+            .watch(directory, RecursiveMode::NonRecursive)
+            .map_err(notify_error)?;
+    }
+
+    *directories = new_directories;
+    Ok(())
+}
+
+fn notify_error(error: notify::Error) -> Error {
+    match error.kind {
+        notify::ErrorKind::Io(error) => Error::io(error),
+        _ => Error::io(io::Error::other(error)),
+    }
+}
@@ -0,0 +1,92 @@
+use serde_ccl::{Assignment, Document, KeyPath, Layers, Segment, Source};
+
+fn layer(name: &str, text: &str) -> Document {
+    Document::parse_with_source(text, Source::named(name)).unwrap()
+}
+
+fn path(path: &str) -> KeyPath {
+    path.parse().unwrap()
+}
+
+#[test]
+fn test_provenance() {
+    let layers = Layers::new()
+        .with_layer(
+            "defaults",
+            layer(
+                "defaults.ccl",
+                "name = app\nserver =\n  port = 80\n  hosts =\n    = localhost\n",
+            ),
+        )
+        .with_layer("system", layer("/etc/app.ccl", "server =\n  port = 443\n"))
+        .with_layer(
+            "user",
+            layer(
+                "~/.app.ccl",
+                "/= User settings\nserver =\n  hosts =\n    = example.com\n",
+            ),
+        );
+
+    let (document, provenance) = layers.merge_with_provenance();
+    assert_eq!(document, layers.merge());
+
+    let port = provenance.get(&path("server.port")).unwrap();
+    assert_eq!(port.layer(), "system");
+    assert_eq!(port.origin().source(), &Source::named("/etc/app.ccl"));
+    assert_eq!(port.origin().key_span().line(), 2);
+    assert_eq!(port.origin().key_span().column(), 3);
+
+    let history = provenance.history(&path("server.port"));
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].to_string(), "defaults.ccl:3:3 (defaults)");
+    assert_eq!(history[1].to_string(), "/etc/app.ccl:2:3 (system)");
+
+    let hosts = provenance
+        .history(&path("server.hosts"))
+        .iter()
+        .map(Assignment::layer)
+        .collect::<Vec<_>>();
+    assert_eq!(hosts, ["defaults", "user"]);
+
+    let paths = provenance
+        .iter()
+        .map(|(path, _)| path.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(paths, ["name", "server", "server.hosts", "server.port"]);
+    assert!(provenance.get(&path("/")).is_none());
+}
+
+#[test]
+fn test_provenance_replaced_block() {
+    let layers = Layers::new()
+        .with_layer("defaults", layer("a.ccl", "server =\n  port = 80\n"))
+        .with_layer("user", layer("b.ccl", "server = disabled\n"));
+
+    let (_, provenance) = layers.merge_with_provenance();
+    assert!(provenance.get(&path("server.port")).is_none());
+    assert!(provenance.history(&path("server.port")).is_empty());
+    assert_eq!(provenance.get(&path("server")).unwrap().layer(), "user");
+}
+
+#[test]
+fn test_provenance_dotted_key() {
+    let layers = Layers::new().with_layer("defaults", layer("a.ccl", "a.b = 1\na =\n  b = 2\n"));
+
+    let (_, provenance) = layers.merge_with_provenance();
+    let dotted = [Segment::Key("a.b".into())]
+        .into_iter()
+        .collect::<KeyPath>();
+    assert_eq!(
+        provenance.get(&dotted).unwrap().origin().key_span().line(),
+        1
+    );
+    assert_eq!(
+        provenance
+            .get(&path("a.b"))
+            .unwrap()
+            .origin()
+            .key_span()
+            .line(),
+        3
+    );
+}
@@ -0,0 +1,109 @@
+#![cfg(feature = "std")]
+
+use serde::Deserialize;
+use serde_ccl::{Document, EnvOverrides, Layers, ListPolicy, MergeOptions, Source};
+
+#[derive(Debug, Deserialize)]
+struct Config {
+    database: Database,
+    servers: Vec<Server>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Database {
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, Deserialize)]
+struct Server {
+    name: String,
+}
+
+const CCL: &str = "
+database =
+  host = localhost
+  port = 3306
+servers =
+  =
+    name = a
+";
+
+#[test]
+fn test_env_overrides() {
+    let env = EnvOverrides::new("APP").load_from([
+        ("APP__SERVERS__10__NAME", "c"),
+        ("APP__DATABASE__PORT", "5432"),
+        ("APP__SERVERS__2__NAME", "b"),
+        ("APP_DATABASE__HOST", "ignored"),
+        ("DATABASE__HOST", "ignored"),
+    ]);
+
+    assert_eq!(
+        env.to_string(),
+        "database =\n    port = 5432\nservers =\n    =\n        name = b\n    =\n        name = c\n",
+    );
+
+    let port = env.get("database").unwrap().value().as_block().unwrap();
+    let port = port.get("port").unwrap();
+    assert_eq!(
+        port.origin().source(),
+        &Source::environment("APP__DATABASE__PORT"),
+    );
+    assert_eq!(
+        port.origin().source().variable(),
+        Some("APP__DATABASE__PORT"),
+    );
+
+    let config = Layers::new()
+        .with_layer("file", Document::parse(CCL).unwrap())
+        .with_layer("environment", env.clone())
+        .deserialize::<Config>()
+        .unwrap();
+
+    assert_eq!(config.database.host, "localhost");
+    assert_eq!(config.database.port, 5432);
+
+    let names = config.servers.iter().map(|server| server.name.as_str());
+    assert_eq!(names.collect::<Vec<_>>(), ["a", "b", "c"]);
+
+    let config = Layers::new()
+        .with_options(MergeOptions::new().with_list_policy(ListPolicy::Replace))
+        .with_layer("file", Document::parse(CCL).unwrap())
+        .with_layer("environment", env)
+        .deserialize::<Config>()
+        .unwrap();
+
+    assert_eq!(config.servers.len(), 2);
+}
+
+#[test]
+fn test_env_overrides_separator() {
+    let env = EnvOverrides::new("app")
+        .with_separator("_")
+        .with_lowercase_keys(false)
+        .load_from([("app_Database_Port", "1"), ("app__x", "ignored")]);
+
+    assert_eq!(env.to_string(), "Database =\n    Port = 1\n");
+}
+
+#[test]
+fn test_env_overrides_error() {
+    let env = EnvOverrides::new("APP").load_from([("APP__DATABASE__PORT", "http")]);
+
+    let error = Layers::new()
+        .with_layer("file", Document::parse(CCL).unwrap())
+        .with_layer("environment", env)
+        .deserialize::<Config>()
+        .unwrap_err();
+
+    assert_eq!(
+        error.input(),
+        Some(&Source::environment("APP__DATABASE__PORT")),
+    );
+    assert_eq!(error.line(), 0);
+    assert_eq!(
+        error.to_string(),
+        "invalid int in environment variable APP__DATABASE__PORT",
+    );
+}
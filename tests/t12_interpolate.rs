@@ -0,0 +1,85 @@
+#![cfg(feature = "std")]
+
+use serde_ccl::{Document, EnvLookup, EnvOverrides, ErrorKind, Source};
+use std::collections::{BTreeMap, HashMap};
+
+#[test]
+fn test_interpolate() {
+    let mut document = Document::parse(
+        "server =
+  url = ${SCHEME:-http}://${HOST}/${EMPTY:-index}
+  motd =
+    Welcome to ${HOST}!
+    Use $${HOST} or $HOST literally.
+",
+    )
+    .unwrap();
+
+    let lookup = HashMap::from([("HOST", "example.com"), ("EMPTY", "")]);
+    document.interpolate(&lookup).unwrap();
+
+    let server = document.get("server").unwrap().value().as_block().unwrap();
+    assert_eq!(
+        server.get("url").unwrap().value().as_text(),
+        Some("http://example.com/index"),
+    );
+    assert_eq!(
+        server.get("motd").unwrap().value().as_text(),
+        Some("Welcome to example.com!\n    Use ${HOST} or $HOST literally."),
+    );
+}
+
+#[test]
+fn test_interpolate_env() {
+    let mut document = Document::parse("name = ${CARGO_PKG_NAME}\n").unwrap();
+    document.interpolate(&EnvLookup).unwrap();
+    assert_eq!(document.to_string(), "name = serde_ccl\n");
+}
+
+#[test]
+fn test_interpolate_undefined() {
+    let mut document = Document::parse_with_source(
+        "motd =\n  Welcome to ${HOST},\n  ${USER}!\n",
+        Source::named("motd.ccl"),
+    )
+    .unwrap();
+
+    let lookup = BTreeMap::from([("HOST", "example.com")]);
+    let error = document.interpolate(&lookup).unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::Interpolation);
+    assert_eq!(error.input(), Some(&Source::named("motd.ccl")));
+    assert_eq!(error.line(), 3);
+    assert_eq!(error.column(), 3);
+    assert_eq!(
+        error.to_string(),
+        "undefined variable USER in motd.ccl at line 3 column 3",
+    );
+}
+
+#[test]
+fn test_interpolate_invalid() {
+    let lookup = BTreeMap::<String, String>::new();
+
+    for (text, column) in [("a = é ${\n", 8), ("a = ${}\n", 5), ("a = ${:-x}\n", 5)] {
+        let mut document = Document::parse(text).unwrap();
+        let error = document.interpolate(&lookup).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::Interpolation);
+        assert_eq!(
+            error.to_string(),
+            format!("invalid interpolation in <anonymous> at line 1 column {column}")
+        );
+    }
+}
+
+#[test]
+fn test_interpolate_env_overrides() {
+    let mut document = EnvOverrides::new("APP").load_from([("APP__URL", "${HOST}")]);
+    let error = document.interpolate(&EnvLookup).unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "undefined variable HOST in environment variable APP__URL",
+    );
+}
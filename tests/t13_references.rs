@@ -0,0 +1,117 @@
+use serde::Deserialize;
+use serde_ccl::{Document, ErrorKind, Layers, Source};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Deserialize)]
+struct Config {
+    server: Server,
+    client: Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, Deserialize)]
+struct Client {
+    url: String,
+    port: u16,
+}
+
+const CCL: &str = "
+client =
+  url = ${SCHEME}://${server.host}:${client.port}
+  port = ${server.port}
+server =
+  host = ${HOST:-localhost}
+  port = 8080
+";
+
+#[test]
+fn test_references() {
+    let mut document = Document::parse(CCL).unwrap();
+    let lookup = BTreeMap::from([("SCHEME", "https")]);
+    document.resolve_references(&lookup).unwrap();
+
+    let config = serde_ccl::from_document::<Config>(&document).unwrap();
+    assert_eq!(config.server.host, "localhost");
+    assert_eq!(config.server.port, 8080);
+    assert_eq!(config.client.url, "https://localhost:8080");
+    assert_eq!(config.client.port, 8080);
+}
+
+#[test]
+fn test_references_list_items() {
+    let mut document =
+        Document::parse("ports =\n  = 80\n  = 443\nhttp = ${ports.0}\nhttps = ${ports[1]}\n")
+            .unwrap();
+    document
+        .resolve_references(&BTreeMap::<&str, &str>::new())
+        .unwrap();
+
+    assert_eq!(document.get("http").unwrap().value().as_text(), Some("80"));
+    assert_eq!(
+        document.get("https").unwrap().value().as_text(),
+        Some("443")
+    );
+}
+
+#[test]
+fn test_references_merged() {
+    let user = Document::parse("server =\n  port = 9000\n").unwrap();
+    let mut document = Layers::new()
+        .with_layer("defaults", Document::parse(CCL).unwrap())
+        .with_layer("user", user)
+        .merge();
+
+    let lookup = BTreeMap::from([("SCHEME", "http")]);
+    document.resolve_references(&lookup).unwrap();
+
+    let config = serde_ccl::from_document::<Config>(&document).unwrap();
+    assert_eq!(config.client.url, "http://localhost:9000");
+}
+
+#[test]
+fn test_references_unresolved() {
+    let mut document = Document::parse_with_source(CCL, Source::named("app.ccl")).unwrap();
+    let error = document
+        .resolve_references(&BTreeMap::<String, String>::new())
+        .unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::Interpolation);
+    assert_eq!(
+        error.to_string(),
+        "unresolved reference SCHEME in app.ccl at line 3 column 9",
+    );
+
+    let mut document = Document::parse("a = ${server}\nserver =\n  port = 1\n").unwrap();
+    let error = document
+        .resolve_references(&BTreeMap::<String, String>::new())
+        .unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "unresolved reference server in <anonymous> at line 1 column 5",
+    );
+}
+
+#[test]
+fn test_references_cycle() {
+    let mut document = Document::parse_with_source(
+        "a = ${b}\nb =\n  x ${c.d:-default}\nc =\n  d = ${a}\n",
+        Source::named("cycle.ccl"),
+    )
+    .unwrap();
+
+    let error = document
+        .resolve_references(&BTreeMap::<String, String>::new())
+        .unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::Interpolation);
+    assert_eq!(
+        error.to_string(),
+        "reference cycle through b in cycle.ccl at line 1 column 5",
+    );
+}
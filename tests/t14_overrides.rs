@@ -0,0 +1,153 @@
+use serde::Deserialize;
+use serde_ccl::{Document, ErrorKind, KeyPath, Override, Source};
+
+#[derive(Debug, Deserialize)]
+struct Config {
+    database: Database,
+    servers: Vec<Server>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Database {
+    port: u16,
+}
+
+#[derive(Debug, Deserialize)]
+struct Server {
+    host: String,
+    tags: Vec<String>,
+}
+
+const CCL: &str = "
+database =
+  port = 5432
+servers =
+  =
+    host = a.com
+    tags =
+  =
+    host = b.com
+    tags =
+      = main
+";
+
+fn apply(args: &[&str]) -> serde_ccl::Result<Document> {
+    let mut document = Document::parse(CCL).unwrap();
+
+    for arg in args {
+        arg.parse::<Override>()?.apply(&mut document)?;
+    }
+
+    Ok(document)
+}
+
+#[test]
+fn test_overrides() {
+    let document = apply(&[
+        "database.port=6543",
+        "servers[0].tags[]=backup",
+        "servers[1].tags[0]=primary",
+        "servers[].host=c.com",
+        "servers[2].tags=",
+    ])
+    .unwrap();
+
+    let config = serde_ccl::from_document::<Config>(&document).unwrap();
+    assert_eq!(config.database.port, 6543);
+    assert_eq!(config.servers[0].tags, ["backup"]);
+    assert_eq!(config.servers[1].tags, ["primary"]);
+    assert_eq!(config.servers[2].host, "c.com");
+    assert!(config.servers[2].tags.is_empty());
+}
+
+#[test]
+fn test_overrides_invalid_path() {
+    for (arg, column) in [
+        ("database..port=1", 10),
+        ("servers[x]=1", 9),
+        ("servers[0]x=1", 11),
+        ("=1", 1),
+        ("database.=1", 9),
+    ] {
+        let error = arg.parse::<Override>().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Syntax, "{arg}");
+        assert_eq!(error.input(), Some(&Source::argument(arg)));
+        assert_eq!(error.column(), column, "{arg}");
+    }
+
+    let error = "database.port".parse::<Override>().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "expected equal sign in argument `database.port` at line 1 column 14",
+    );
+}
+
+#[test]
+fn test_overrides_list_items() {
+    let document = apply(&["servers.1.host=c.com", "servers.1.tags.0=primary"]).unwrap();
+    let config = serde_ccl::from_document::<Config>(&document).unwrap();
+    assert_eq!(config.servers.len(), 2);
+    assert_eq!(config.servers[1].tags, ["primary"]);
+    assert_eq!(config.servers[1].host, "c.com");
+
+    let mut document = Document::parse("ports =\n  = 1\n  = 2\n").unwrap();
+    "ports.1=9"
+        .parse::<Override>()
+        .unwrap()
+        .apply(&mut document)
+        .unwrap();
+    assert_eq!(
+        serde_ccl::from_document::<Vec<u16>>(
+            document.get("ports").unwrap().value().as_block().unwrap()
+        )
+        .unwrap(),
+        [1, 9],
+    );
+
+    let mut document = Document::parse("codes =\n  200 = OK\n").unwrap();
+    "codes.404=Not Found"
+        .parse::<Override>()
+        .unwrap()
+        .apply(&mut document)
+        .unwrap();
+    assert_eq!(
+        document
+            .get_path(&"codes.404".parse().unwrap())
+            .unwrap()
+            .value()
+            .as_text(),
+        Some("Not Found")
+    );
+}
+
+#[test]
+fn test_overrides_out_of_bounds() {
+    let error = apply(&["servers[2].host=c.com"]).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "index 2 out of bounds in argument `servers[2].host=c.com`",
+    );
+
+    let error = apply(&["servers.2.host=c.com"]).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "index 2 out of bounds in argument `servers.2.host=c.com`",
+    );
+}
+
+#[test]
+fn test_overrides_invalid_value() {
+    let document = apply(&["database.port=many"]).unwrap();
+    let error = serde_ccl::from_document::<Config>(&document).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid int in argument `database.port=many`",
+    );
+}
+
+#[test]
+fn test_key_path_display() {
+    for path in ["a", "a.b", "a[0]", "a[0][1].b", "[]", "[2].x"] {
+        assert_eq!(path.parse::<KeyPath>().unwrap().to_string(), path);
+    }
+}
@@ -0,0 +1,123 @@
+#![cfg(feature = "watch")]
+
+use serde::Deserialize;
+use serde_ccl::{WatchEvent, Watcher};
+use std::fs;
+use std::sync::{mpsc, Arc, OnceLock};
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Config {
+    name: String,
+    port: u16,
+}
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[test]
+fn test_watch() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("app.ccl");
+    fs::create_dir(dir.path().join("conf.d")).unwrap();
+    fs::write(&root, "name = app\nimports = conf.d/*.ccl\n").unwrap();
+    fs::write(dir.path().join("conf.d/port.ccl"), "port = 80\n").unwrap();
+
+    let (sender, receiver) = mpsc::channel();
+    let watcher = Watcher::<Config>::new(root.to_str().unwrap(), move |event| {
+        let _ = sender.send(event);
+    })
+    .unwrap();
+
+    assert_eq!(watcher.current().port, 80);
+
+    fs::write(dir.path().join("conf.d/port.ccl"), "port = 8080\n").unwrap();
+
+    let WatchEvent::Changed { old, new } = receiver.recv_timeout(TIMEOUT).unwrap() else {
+        panic!("expected a change");
+    };
+
+    assert_eq!(old.port, 80);
+    assert_eq!(new.port, 8080);
+    assert_eq!(watcher.current().port, 8080);
+
+    fs::write(dir.path().join("conf.d/port.ccl"), "port = http\n").unwrap();
+
+    let WatchEvent::Failed(error) = receiver.recv_timeout(TIMEOUT).unwrap() else {
+        panic!("expected an error");
+    };
+
+    assert!(error.input().unwrap().name().unwrap().ends_with("port.ccl"));
+    assert_eq!(watcher.current().port, 8080);
+}
+
+#[test]
+fn test_watch_reload() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("app.ccl");
+    fs::write(&root, "name = app\nport = 80\n").unwrap();
+
+    let (sender, receiver) = mpsc::channel();
+    let watcher = Watcher::<Config>::new(root.to_str().unwrap(), move |event| {
+        if let WatchEvent::Changed { new, .. } = event {
+            let _ = sender.send(new);
+        }
+    })
+    .unwrap();
+
+    assert!(!watcher.reload().unwrap());
+
+    fs::write(&root, "name = app\nport =\n").unwrap();
+    assert!(watcher.reload().is_err());
+    assert_eq!(watcher.current().port, 80);
+
+    fs::write(&root, "name = other\nport = 80\n").unwrap();
+    let _ = watcher.reload();
+
+    let new = loop {
+        let new = receiver.recv_timeout(TIMEOUT).unwrap();
+
+        if new.name == "other" {
+            break new;
+        }
+    };
+
+    assert_eq!(*watcher.current(), *new);
+}
+
+#[test]
+fn test_watch_reentrant_callback() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("app.ccl");
+    fs::write(&root, "name = app\nport = 80\n").unwrap();
+
+    let slot = Arc::new(OnceLock::<Watcher<Config>>::new());
+    let weak_slot = Arc::downgrade(&slot);
+    let callback_root = root.clone();
+
+    let (sender, receiver) = mpsc::channel();
+    let watcher = Watcher::<Config>::new(root.to_str().unwrap(), move |event| {
+        let WatchEvent::Changed { new, .. } = event else {
+            return;
+        };
+
+        let Some(slot) = weak_slot.upgrade() else {
+            return;
+        };
+
+        let watcher = slot.get().unwrap();
+
+        if new.port == 8080 {
+            fs::write(&callback_root, "name = app\nport = 9090\n").unwrap();
+            assert!(watcher.reload().unwrap());
+        }
+
+        let _ = sender.send((new.port, watcher.current().port));
+    })
+    .unwrap();
+
+    assert!(slot.set(watcher).is_ok());
+
+    fs::write(&root, "name = app\nport = 8080\n").unwrap();
+    assert_eq!(receiver.recv_timeout(TIMEOUT).unwrap(), (8080, 9090));
+    assert_eq!(receiver.recv_timeout(TIMEOUT).unwrap(), (9090, 9090));
+}
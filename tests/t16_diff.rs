@@ -0,0 +1,148 @@
+use serde_ccl::{ChangeKind, Document, Source};
+
+fn changes(old: &str, new: &str) -> Vec<String> {
+    let old = Document::parse(old).unwrap();
+    let new = Document::parse(new).unwrap();
+
+    serde_ccl::diff(&old, &new)
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test]
+fn test_diff() {
+    let old = "
+/= Server settings
+name = app
+server =
+  port = 80
+  hosts =
+    = a.com
+    = b.com
+  timeout = 30
+motd =
+  Hello,
+  world!
+";
+
+    let new = "
+name = app
+server =
+    hosts =
+        = a.com
+        = c.com
+        = d.com
+    port = 8080
+    tls =
+        cert = cert.pem
+motd =
+    Hello,
+    world!
+";
+
+    assert_eq!(
+        changes(old, new),
+        [
+            "changed server.port",
+            "changed server.hosts[1]",
+            "added server.hosts[2]",
+            "removed server.timeout",
+            "added server.tls",
+        ],
+    );
+}
+
+#[test]
+fn test_diff_no_changes() {
+    let old = "/= Comment\na = 1\nb =\n  = x\na = 2\n";
+    let new = "a = 2\nb =\n    /= Item\n    = x\n";
+    assert!(changes(old, new).is_empty());
+
+    assert!(changes("a =\n", "a =\n  /= Nothing yet\n").is_empty());
+}
+
+#[test]
+fn test_diff_kinds() {
+    assert_eq!(
+        changes("a = 1\nb =\n  c = 1\n", "a =\n  x = 1\nb = 1\n"),
+        ["changed a", "changed b"],
+    );
+
+    assert_eq!(changes("= a\n= b\n", "= a\n"), ["removed [1]"]);
+
+    assert_eq!(
+        changes("motd =\n  \ta\n  \tb\n", "motd =\n  \ta\n  b\n"),
+        ["changed motd"],
+    );
+}
+
+#[test]
+fn test_diff_positions() {
+    let old = Document::parse_with_source("port = 80\n", Source::named("old.ccl")).unwrap();
+    let new = Document::parse_with_source("\nport = 8080\n", Source::named("new.ccl")).unwrap();
+
+    let changes = serde_ccl::diff(&old, &new);
+    let [change] = changes.as_slice() else {
+        panic!("expected a single change");
+    };
+
+    assert_eq!(change.kind(), ChangeKind::Changed);
+
+    let old_entry = change.old_entry().unwrap();
+    assert_eq!(old_entry.origin().source(), &Source::named("old.ccl"));
+    assert_eq!(old_entry.origin().value_span().line(), 1);
+    assert_eq!(old_entry.value().as_text(), Some("80"));
+
+    let new_entry = change.new_entry().unwrap();
+    assert_eq!(new_entry.origin().source(), &Source::named("new.ccl"));
+    assert_eq!(new_entry.origin().value_span().line(), 2);
+    assert_eq!(new_entry.value().as_text(), Some("8080"));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_diff_cli() {
+    use std::fs;
+    use std::process::Command;
+
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("old.ccl"),
+        "name = app\nport = 80\ntimeout = 30\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("new.ccl"),
+        "name = app\n\nport = 8080\ntls = on\n",
+    )
+    .unwrap();
+
+    let diff = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_ccl"))
+            .current_dir(dir.path())
+            .arg("diff")
+            .args(args)
+            .output()
+            .unwrap()
+    };
+
+    let output = diff(&["old.ccl", "new.ccl"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "changed port (old.ccl:2 -> new.ccl:3)\n\
+         removed timeout (old.ccl:3)\n\
+         added tls (new.ccl:4)\n",
+    );
+
+    assert!(!diff(&["--exit-code", "old.ccl", "new.ccl"])
+        .status
+        .success());
+
+    let output = diff(&["--exit-code", "old.ccl", "old.ccl"]);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+
+    assert!(!diff(&["old.ccl", "missing.ccl"]).status.success());
+}
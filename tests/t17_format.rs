@@ -0,0 +1,150 @@
+use serde::Deserialize;
+use serde_ccl::{Document, Source};
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Config {
+    name: String,
+    server: Server,
+    note: String,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Server {
+    port: u16,
+    motd: String,
+    hosts: Vec<String>,
+}
+
+const CCL: &str = "
+
+name=app
+
+
+/= Servers
+server =
+  port  =  80   
+
+  motd =
+      Hello,
+        world!
+  hosts =
+    = a.com
+
+
+    = b.com
+note = one
+  two
+";
+
+const FORMATTED: &str = "name = app
+
+/= Servers
+server =
+    port = 80
+
+    motd =
+      Hello,
+        world!
+    hosts =
+        = a.com
+
+        = b.com
+note = one
+  two
+";
+
+#[test]
+fn test_format() {
+    let formatted = serde_ccl::format(CCL).unwrap();
+    assert_eq!(formatted, FORMATTED);
+    assert_eq!(serde_ccl::format(&formatted).unwrap(), formatted);
+}
+
+#[test]
+fn test_format_keeps_values() {
+    let formatted = serde_ccl::format(CCL).unwrap();
+
+    assert_eq!(
+        Document::parse(&formatted).unwrap(),
+        Document::parse(CCL).unwrap(),
+    );
+
+    assert_eq!(
+        serde_ccl::from_str::<Config>(&formatted).unwrap(),
+        serde_ccl::from_str::<Config>(CCL).unwrap(),
+    );
+}
+
+#[test]
+fn test_format_shallow_text() {
+    let formatted = serde_ccl::format("a =\n  b = 1\n  c =\n   one\n    two\nd=2\n").unwrap();
+    assert_eq!(formatted, "a =\n  b = 1\n  c =\n   one\n    two\nd = 2\n");
+
+    let formatted = serde_ccl::format("a =\n  b =\n     one\n      two\n").unwrap();
+    assert_eq!(formatted, "a =\n    b =\n     one\n      two\n");
+}
+
+#[test]
+fn test_format_ambiguous_blocks() {
+    let formatted = serde_ccl::format("script =\n  echo a=b\n").unwrap();
+    assert_eq!(formatted, "script =\n  echo a=b\n");
+
+    let formatted = serde_ccl::format("desc =\n  hello world\n  key = v\nnext=1\n").unwrap();
+    assert_eq!(formatted, "desc =\n  hello world\n  key = v\nnext = 1\n");
+}
+
+#[test]
+fn test_format_keeps_json_values() {
+    let texts = [
+        CCL,
+        "script =\n  echo a=b\n",
+        "desc =\n  hello world\n  key = v\nnext = 1\n",
+        "a =\n  b = 1\n   c = 2\n",
+        "a =\n  b =\n   one\n    two\n",
+        "a =\n  b =\n    c =\n     d = 1\n    e = 2\n",
+        "a =\n  = 1\n  =\n    b = 2\n",
+    ];
+
+    for text in texts {
+        let formatted = serde_ccl::format(text).unwrap();
+
+        assert_eq!(
+            serde_ccl::from_str::<serde_json::Value>(&formatted).unwrap(),
+            serde_ccl::from_str::<serde_json::Value>(text).unwrap(),
+            "{text:?} was formatted as {formatted:?}",
+        );
+    }
+}
+
+#[test]
+fn test_format_error() {
+    let error = serde_ccl::format_with_source("a = 1\nb\n", Source::named("app.ccl")).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "expected equal sign in app.ccl at line 3 column 1",
+    );
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_format_cli() {
+    use std::fs;
+    use std::process::Command;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.ccl");
+    fs::write(&path, CCL).unwrap();
+
+    let ccl = || Command::new(env!("CARGO_BIN_EXE_ccl"));
+
+    let output = ccl().args(["fmt", "--check"]).arg(&path).output().unwrap();
+    assert!(!output.status.success());
+    assert_eq!(fs::read_to_string(&path).unwrap(), CCL);
+
+    let output = ccl().arg("fmt").arg(&path).output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(&path).unwrap(), FORMATTED);
+
+    let output = ccl().args(["fmt", "--check"]).arg(&path).output().unwrap();
+    assert!(output.status.success());
+}
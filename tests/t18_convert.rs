@@ -0,0 +1,223 @@
+use serde::{Deserialize, Serialize};
+use serde_ccl::ErrorKind;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Config {
+    name: String,
+    debug: bool,
+    ratio: f64,
+    ports: Vec<u16>,
+    limits: BTreeMap<u32, u32>,
+    backup: Option<String>,
+    primary: Option<String>,
+    colors: Vec<Color>,
+    matrix: Vec<Vec<u8>>,
+    pair: (u8, char),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Color {
+    None,
+    Named(String),
+    Rgb(u8, u8, u8),
+    Hsl { h: u16, s: u8, l: u8 },
+}
+
+const CCL: &str = "\
+name = app
+debug = true
+ratio = 0.5
+ports =
+    = 80
+    = 443
+limits =
+    1 = 10
+    2 = 20
+backup =
+primary = a.com
+colors =
+    =
+        None =
+    =
+        Named = red
+    =
+        Rgb =
+            = 1
+            = 2
+            = 3
+    =
+        Hsl =
+            h = 120
+            s = 50
+            l = 25
+matrix =
+    =
+        = 1
+    =
+pair =
+    = 7
+    = x
+";
+
+fn config() -> Config {
+    Config {
+        name: "app".into(),
+        debug: true,
+        ratio: 0.5,
+        ports: vec![80, 443],
+        limits: BTreeMap::from([(1, 10), (2, 20)]),
+        backup: None,
+        primary: Some("a.com".into()),
+        colors: vec![
+            Color::None,
+            Color::Named("red".into()),
+            Color::Rgb(1, 2, 3),
+            Color::Hsl {
+                h: 120,
+                s: 50,
+                l: 25,
+            },
+        ],
+        matrix: vec![vec![1], vec![]],
+        pair: (7, 'x'),
+    }
+}
+
+#[test]
+fn test_serialize() {
+    let ccl = serde_ccl::to_string(&config()).unwrap();
+    assert_eq!(ccl, CCL);
+    assert_eq!(serde_ccl::from_str::<Config>(&ccl).unwrap(), config());
+}
+
+#[test]
+fn test_serialize_scalar() {
+    assert_eq!(serde_ccl::to_string(&42).unwrap(), "42\n");
+    assert_eq!(serde_ccl::from_str::<u32>("42\n").unwrap(), 42);
+    assert_eq!(serde_ccl::to_string(&None::<u32>).unwrap(), "");
+
+    let ccl = serde_ccl::to_string(&BTreeMap::from([("a", " one  \n")])).unwrap();
+    assert_eq!(ccl, "a = one\n");
+}
+
+#[test]
+fn test_serialize_errors() {
+    let error = serde_ccl::to_string(&BTreeMap::from([("a=b", 1)])).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Semantic);
+    assert_eq!(error.to_string(), "invalid key `a=b`");
+
+    for key in ["", "/", " a"] {
+        let error = serde_ccl::to_string(&BTreeMap::from([(key, 1)])).unwrap_err();
+        assert_eq!(error.to_string(), format!("invalid key `{key}`"));
+    }
+
+    let error = serde_ccl::to_string(&BTreeMap::from([((1, 2), 1)])).unwrap_err();
+    assert_eq!(error.to_string(), "key must be a scalar");
+
+    let error = serde_ccl::to_document(&"text").unwrap_err();
+    assert_eq!(error.to_string(), "expected a map or a sequence");
+}
+
+#[test]
+fn test_to_document() {
+    let document = serde_ccl::to_document(&config()).unwrap();
+    assert_eq!(document, serde_ccl::Document::parse(CCL).unwrap());
+}
+
+#[test]
+fn test_deserialize_any() {
+    let value = serde_ccl::from_str::<Value>(CCL).unwrap();
+
+    assert_eq!(
+        value,
+        json!({
+            "name": "app",
+            "debug": "true",
+            "ratio": "0.5",
+            "ports": ["80", "443"],
+            "limits": { "1": "10", "2": "20" },
+            "backup": "",
+            "primary": "a.com",
+            "colors": [
+                { "None": "" },
+                { "Named": "red" },
+                { "Rgb": ["1", "2", "3"] },
+                { "Hsl": { "h": "120", "s": "50", "l": "25" } },
+            ],
+            "matrix": [["1"], ""],
+            "pair": ["7", "x"],
+        }),
+    );
+}
+
+#[test]
+fn test_deserialize_any_comments() {
+    let ccl =
+        "/= Servers\nservers =\n  /= Primary\n  = a.com\n  = b.com\nmotd =\n  Hello,\n    world!\n";
+    let value = serde_ccl::from_str::<Value>(ccl).unwrap();
+
+    assert_eq!(
+        value,
+        json!({
+            "servers": ["a.com", "b.com"],
+            "motd": "Hello,\n    world!",
+        }),
+    );
+
+    assert_eq!(serde_ccl::from_str::<Value>("text").unwrap(), json!("text"));
+}
+
+#[test]
+fn test_round_trip() {
+    let json = json!({
+        "name": "app",
+        "servers": [
+            { "host": "a.com", "tags": ["main", "eu"] },
+            { "host": "b.com", "tags": ["backup"] },
+        ],
+        "database": { "url": "postgres://localhost/app" },
+    });
+
+    let ccl = serde_ccl::to_string(&json).unwrap();
+    assert_eq!(serde_ccl::from_str::<Value>(&ccl).unwrap(), json);
+
+    let toml = toml::to_string(&serde_ccl::from_str::<Value>(&ccl).unwrap()).unwrap();
+    let value = toml::from_str::<Value>(&toml).unwrap();
+    assert_eq!(serde_ccl::to_string(&value).unwrap(), ccl);
+
+    let yaml = serde_yaml::to_string(&serde_ccl::from_str::<Value>(&ccl).unwrap()).unwrap();
+    let value = serde_yaml::from_str::<Value>(&yaml).unwrap();
+    assert_eq!(serde_ccl::to_string(&value).unwrap(), ccl);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_convert_cli() {
+    use std::fs;
+    use std::process::Command;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.json");
+    fs::write(&path, r#"{ "name": "app", "ports": [80, 443] }"#).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ccl"))
+        .args(["convert", "--to", "ccl"])
+        .arg(&path)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "name = app\nports =\n    = 80\n    = 443\n",
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ccl"))
+        .args(["convert", "--to", "ccl"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+}
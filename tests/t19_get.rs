@@ -0,0 +1,154 @@
+use serde::Deserialize;
+use serde_ccl::{Document, KeyPath, Source};
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Database {
+    host: String,
+    ports: Vec<u16>,
+}
+
+const CCL: &str = "
+database =
+  host = localhost
+  ports =
+    = 5432
+    = 5433
+limits =
+  1 = 10
+  = 20
+hosts = a.com
+hosts = b.com
+";
+
+fn document() -> Document {
+    Document::parse_with_source(CCL, Source::named("app.ccl")).unwrap()
+}
+
+fn path(path: &str) -> KeyPath {
+    path.parse().unwrap()
+}
+
+#[test]
+fn test_get() {
+    let document = document();
+
+    assert_eq!(
+        serde_ccl::get::<u16>(&document, &path("database.ports.1")).unwrap(),
+        Some(5433),
+    );
+    assert_eq!(
+        serde_ccl::get::<u16>(&document, &path("database.ports[0]")).unwrap(),
+        Some(5432),
+    );
+    assert_eq!(
+        serde_ccl::get::<Database>(&document, &path("database")).unwrap(),
+        Some(Database {
+            host: "localhost".into(),
+            ports: vec![5432, 5433],
+        }),
+    );
+    assert_eq!(
+        serde_ccl::get::<String>(&document, &path("hosts")).unwrap(),
+        Some("b.com".into()),
+    );
+}
+
+#[test]
+fn test_get_missing() {
+    let document = document();
+
+    for missing in ["port", "database.ports.2", "database.host.name", "hosts[]"] {
+        let value = serde_ccl::get::<String>(&document, &path(missing)).unwrap();
+        assert_eq!(value, None, "{missing}");
+    }
+}
+
+#[test]
+fn test_get_numeric_keys() {
+    let document = document();
+
+    assert_eq!(
+        serde_ccl::get::<u32>(&document, &path("limits.1")).unwrap(),
+        Some(10),
+    );
+    assert_eq!(
+        serde_ccl::get::<u32>(&document, &path("limits.0")).unwrap(),
+        Some(20),
+    );
+}
+
+#[test]
+fn test_get_all() {
+    let document = document();
+
+    assert_eq!(
+        serde_ccl::get_all::<String>(&document, &path("hosts")).unwrap(),
+        ["a.com", "b.com"],
+    );
+
+    let entries = document.get_all_path(&path("database.ports[1]"));
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].origin().value_span().line(), 6);
+
+    let entry = document.get_path(&path("database")).unwrap();
+    assert_eq!(entry.value().as_block().unwrap().len(), 2);
+}
+
+#[test]
+fn test_get_error() {
+    let document = document();
+
+    let error = serde_ccl::get::<u16>(&document, &path("database.host")).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid int in app.ccl at line 3 column 10",
+    );
+
+    let error = serde_ccl::get::<Database>(&document, &path("limits")).unwrap_err();
+    assert_eq!(error.input(), Some(&Source::named("app.ccl")));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_get_cli() {
+    use std::fs;
+    use std::process::Command;
+
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("app.ccl");
+    fs::write(&file, CCL).unwrap();
+
+    let get = |args: &[&str], path: &str| {
+        let output = Command::new(env!("CARGO_BIN_EXE_ccl"))
+            .arg("get")
+            .args(args)
+            .arg(&file)
+            .arg(path)
+            .output()
+            .unwrap();
+
+        assert!(output.status.success(), "{path}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert_eq!(get(&[], "database.ports.1"), "5433\n");
+    assert_eq!(get(&[], "database.ports"), "= 5432\n= 5433\n");
+    assert_eq!(get(&["--all"], "hosts"), "a.com\nb.com\n");
+    assert_eq!(
+        get(&["--json"], "database.ports"),
+        "[\n  \"5432\",\n  \"5433\"\n]\n"
+    );
+    assert_eq!(
+        get(&["--json", "--all"], "hosts"),
+        "[\n  \"a.com\",\n  \"b.com\"\n]\n"
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ccl"))
+        .arg("get")
+        .arg(&file)
+        .arg("port")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+}
@@ -0,0 +1,195 @@
+use serde_ccl::{Editor, ErrorKind, KeyPath, Source};
+
+const CCL: &str = "\
+/= Deployment settings
+name = app
+
+database =
+  /= Primary database
+  host = db.local   
+  port = 5432
+  ports =
+    = 5432
+    = 5433
+
+motd =
+  Hello,
+  world!
+hosts = a.com
+hosts = b.com
+";
+
+fn path(path: &str) -> KeyPath {
+    path.parse().unwrap()
+}
+
+fn set(text: &str, key_path: &str, value: &str) -> String {
+    let mut editor = Editor::parse(text).unwrap();
+    editor.set(&path(key_path), value).unwrap();
+    editor.into_text()
+}
+
+fn unset(text: &str, key_path: &str) -> String {
+    let mut editor = Editor::parse(text).unwrap();
+    assert!(editor.unset(&path(key_path)).unwrap(), "{key_path}");
+    editor.into_text()
+}
+
+#[test]
+fn test_set_existing() {
+    assert_eq!(
+        set(CCL, "database.port", "6543"),
+        CCL.replace("port = 5432", "port = 6543"),
+    );
+    assert_eq!(
+        set(CCL, "database.host", "db.prod"),
+        CCL.replace("host = db.local   ", "host = db.prod   "),
+    );
+    assert_eq!(
+        set(CCL, "database.ports[1]", "6000"),
+        CCL.replace("= 5433", "= 6000"),
+    );
+    assert_eq!(
+        set(CCL, "database.ports.0", "6000"),
+        CCL.replace("    = 5432", "    = 6000"),
+    );
+    assert_eq!(
+        set(CCL, "hosts", "c.com"),
+        CCL.replace("hosts = b.com", "hosts = c.com"),
+    );
+    assert_eq!(
+        set(CCL, "motd", "Bye"),
+        CCL.replace("motd =\n  Hello,\n  world!", "motd = Bye"),
+    );
+    assert_eq!(
+        set(CCL, "database", "none"),
+        CCL.replace(
+            "database =\n  /= Primary database\n  host = db.local   \n  port = 5432\n  ports =\n    = 5432\n    = 5433\n",
+            "database = none\n",
+        ),
+    );
+    assert_eq!(set("a =\nb = 2\n", "a", "1"), "a = 1\nb = 2\n");
+    assert_eq!(set("a = 1", "a", ""), "a =");
+}
+
+#[test]
+fn test_set_multiline() {
+    assert_eq!(
+        set("a =\n  b = 1\n", "a.b", "x\ny"),
+        "a =\n  b = x\n      y\n",
+    );
+}
+
+#[test]
+fn test_set_new() {
+    assert_eq!(
+        set(CCL, "database.user", "admin"),
+        CCL.replace("    = 5433\n", "    = 5433\n  user = admin\n"),
+    );
+    assert_eq!(
+        set(CCL, "database.ports[]", "5434"),
+        CCL.replace("    = 5433\n", "    = 5433\n    = 5434\n"),
+    );
+    assert_eq!(
+        set(CCL, "log.level", "debug"),
+        format!("{CCL}log =\n  level = debug\n"),
+    );
+    assert_eq!(
+        set(CCL, "name.first", "app"),
+        CCL.replace("name = app", "name =\n  first = app"),
+    );
+    assert_eq!(
+        set("a = 1\nb =\n", "b.c.d", "x"),
+        "a = 1\nb =\n    c =\n        d = x\n",
+    );
+    assert_eq!(set("", "a", "1"), "a = 1\n");
+    assert_eq!(set("/= Empty", "a", "1"), "/= Empty\na = 1");
+}
+
+#[test]
+fn test_set_error() {
+    let mut editor = Editor::parse_with_source(CCL, Source::named("app.ccl")).unwrap();
+
+    let error = editor.set(&path("database.ports[2]"), "1").unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Semantic);
+    assert_eq!(error.to_string(), "index 2 out of bounds in app.ccl");
+
+    let error = editor.set(&path("database.ports.2"), "1").unwrap_err();
+    assert_eq!(error.to_string(), "index 2 out of bounds in app.ccl");
+
+    let error = editor.set(&path("log[0]"), "1").unwrap_err();
+    assert_eq!(error.to_string(), "index 0 out of bounds in app.ccl");
+
+    let error = editor.set(&path("a=b"), "1").unwrap_err();
+    assert_eq!(error.to_string(), "invalid key `a=b` in app.ccl");
+
+    assert_eq!(editor.text(), CCL);
+}
+
+#[test]
+fn test_unset() {
+    assert_eq!(
+        unset(CCL, "database.port"),
+        CCL.replace("  port = 5432\n", ""),
+    );
+    assert_eq!(
+        unset(CCL, "database.ports[0]"),
+        CCL.replace("    = 5432\n    = 5433", "    = 5433"),
+    );
+    assert_eq!(
+        unset(CCL, "motd"),
+        CCL.replace("motd =\n  Hello,\n  world!\n", ""),
+    );
+    assert_eq!(
+        unset(CCL, "hosts"),
+        CCL.replace("hosts = a.com\nhosts = b.com\n", ""),
+    );
+    assert_eq!(unset("a = 1\nb = 2", "b"), "a = 1\n");
+
+    let mut editor = Editor::parse(CCL).unwrap();
+    assert!(!editor.unset(&path("database.user")).unwrap());
+    assert_eq!(editor.text(), CCL);
+}
+
+#[test]
+fn test_edit_document() {
+    let mut editor = Editor::parse(CCL).unwrap();
+    editor.set(&path("database.port"), "6543").unwrap();
+
+    let entry = editor.document().get_path(&path("database.port")).unwrap();
+    assert_eq!(entry.value().as_text(), Some("6543"));
+    assert_eq!(entry.origin().value_span().line(), 7);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_edit_cli() {
+    use std::fs;
+    use std::process::Command;
+
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("app.ccl");
+    fs::write(&file, CCL).unwrap();
+
+    let ccl = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_ccl"))
+            .arg(args[0])
+            .arg(&file)
+            .args(&args[1..])
+            .output()
+            .unwrap()
+            .status
+            .success()
+    };
+
+    assert!(ccl(&["set", "database.port", "6543"]));
+    assert!(ccl(&["unset", "motd"]));
+    assert_eq!(
+        fs::read_to_string(&file).unwrap(),
+        CCL.replace("port = 5432", "port = 6543")
+            .replace("motd =\n  Hello,\n  world!\n", ""),
+    );
+
+    assert!(!ccl(&["unset", "motd"]));
+    assert!(!ccl(&["set", "database.ports[5]", "1"]));
+}
@@ -0,0 +1,208 @@
+use serde_ccl::{ErrorKind, Rule};
+
+fn diagnostics(text: &str) -> Vec<String> {
+    serde_ccl::lint(text)
+        .unwrap()
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test]
+fn test_lint_clean() {
+    let text = "\
+/= Settings
+name = app
+database =
+  port = 5432
+  ports =
+    = 5432
+    = 5433
+  /= Limits
+  limits =
+    cpu = 1500mi
+motd =
+  Hello,
+    world!
+";
+
+    assert!(diagnostics(text).is_empty());
+}
+
+#[test]
+fn test_lint_rules() {
+    let text = "
+name = app
+database =
+    host = db.local
+    host = db.prod
+  port = 5432
+  user admin
+  timeout = 30
+ports =
+  = 80
+  /= HTTPS
+  = 443
+  /= End
+name = app2\x20\x20
+\t key = value
+tags =
+\t= a
+";
+
+    assert_eq!(
+        diagnostics(text),
+        [
+            "duplicate-key: duplicate key `host`, first defined on line 4 at line 5 column 5",
+            "inconsistent-indent: expected indentation of 4 spaces, found 2 at line 6 column 3",
+            "missing-eq: missing equal sign, so the line is part of the key on line 8 at line 7 column 3",
+            "inconsistent-indent: expected indentation of 4 spaces, found 2 at line 8 column 3",
+            "comment-in-list: comment between list items at line 11 column 3",
+            "duplicate-key: duplicate key `name`, first defined on line 2 at line 14 column 1",
+            "trailing-whitespace: trailing whitespace at line 14 column 12",
+            "mixed-indent: indentation mixes tabs and spaces at line 15 column 1",
+            "mixed-indent: indentation uses tabs instead of spaces at line 17 column 1",
+        ],
+    );
+}
+
+#[test]
+fn test_lint_missing_eq_at_end() {
+    assert_eq!(
+        diagnostics("a =\n  b = 1\n  c\nz = 3\n"),
+        ["missing-eq: missing equal sign at line 3 column 3"],
+    );
+
+    assert_eq!(
+        diagnostics("port = 80\nhost\n  name\n"),
+        [
+            "missing-eq: missing equal sign at line 2 column 1",
+            "missing-eq: missing equal sign at line 3 column 3",
+        ],
+    );
+
+    assert!(diagnostics("motd =\n  Hello,\n    world!\n").is_empty());
+}
+
+#[test]
+fn test_lint_spans() {
+    let diagnostics = serde_ccl::lint("a = 1 \r\nb = 2\n").unwrap();
+    let [diagnostic] = diagnostics.as_slice() else {
+        panic!("expected a single diagnostic");
+    };
+
+    assert_eq!(diagnostic.rule(), Rule::TrailingWhitespace);
+    assert_eq!(diagnostic.message(), "trailing whitespace");
+    assert_eq!(diagnostic.span().range(), 5..6);
+}
+
+#[test]
+fn test_lint_disable() {
+    let text = "\
+a = 1\x20
+/= ccl-lint: disable-next-line trailing-whitespace
+b = 2\x20
+c =
+  /= ccl-lint: disable duplicate-key, trailing-whitespace
+  d = 1\x20
+  d = 2
+e = 3\x20
+e = 4
+/= ccl-lint: disable-next-line unknown-rule
+f = 5\x20
+/= ccl-lint: disable
+g = 6\x20
+g = 7
+";
+
+    assert_eq!(
+        diagnostics(text),
+        [
+            "trailing-whitespace: trailing whitespace at line 1 column 6",
+            "trailing-whitespace: trailing whitespace at line 8 column 6",
+            "duplicate-key: duplicate key `e`, first defined on line 8 at line 9 column 1",
+            "trailing-whitespace: trailing whitespace at line 11 column 6",
+        ],
+    );
+}
+
+#[test]
+fn test_rule_names() {
+    for rule in Rule::ALL {
+        assert_eq!(rule.name().parse::<Rule>().unwrap(), rule);
+        assert_eq!(rule.to_string(), rule.name());
+    }
+
+    let error = "tabs".parse::<Rule>().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Argument);
+    assert_eq!(error.to_string(), "unknown lint rule `tabs`");
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_lint_cli() {
+    use std::fs;
+    use std::process::Command;
+
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("app.ccl");
+    fs::write(&file, "a = 1\na = 2\u{e9} \nb\n").unwrap();
+
+    let lint = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_ccl"))
+            .arg("lint")
+            .args(args)
+            .arg(&file)
+            .output()
+            .unwrap();
+
+        assert!(!output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let name = file.display();
+    assert_eq!(
+        lint(&[]),
+        format!(
+            "{name}:2:1: duplicate key `a`, first defined on line 1 [duplicate-key]\n\
+             {name}:2:8: trailing whitespace [trailing-whitespace]\n\
+             {name}:3:1: missing equal sign [missing-eq]\n"
+        ),
+    );
+
+    let json = serde_json::from_str::<serde_json::Value>(&lint(&["--format", "json"])).unwrap();
+    assert_eq!(json[1]["rule"], "trailing-whitespace");
+    assert_eq!(json[1]["column"], 8);
+    assert_eq!(json[1]["end_column"], 9);
+    assert_eq!(json[2]["rule"], "missing-eq");
+    assert_eq!(json[2]["line"], 3);
+
+    let sarif = serde_json::from_str::<serde_json::Value>(&lint(&["--format", "sarif"])).unwrap();
+    assert_eq!(sarif["version"], "2.1.0");
+
+    let results = &sarif["runs"][0]["results"];
+    assert_eq!(results.as_array().unwrap().len(), 3);
+    assert_eq!(results[1]["ruleId"], "trailing-whitespace");
+    assert_eq!(results[2]["ruleId"], "missing-eq");
+
+    let region = &results[1]["locations"][0]["physicalLocation"]["region"];
+    assert_eq!(region["startColumn"], 7);
+    assert_eq!(region["endColumn"], 8);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ccl"))
+        .args([
+            "lint",
+            "--disable",
+            "duplicate-key",
+            "--disable",
+            "trailing-whitespace",
+            "--disable",
+            "missing-eq",
+        ])
+        .arg(&file)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
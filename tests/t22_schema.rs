@@ -0,0 +1,305 @@
+#![cfg(feature = "schema")]
+
+use serde_ccl::{Document, ErrorKind, Schema};
+use serde_json::json;
+
+const CCL: &str = "\
+/= Deployment settings
+name = app
+replicas = 0
+debug = yes
+mode = fast
+database =
+  host = db.local
+  port = 70000
+  ports =
+    = 5432
+    = x
+tags =
+  = a
+  = bb
+  = ccc
+extra = 1
+";
+
+fn schema() -> Schema {
+    Schema::new(json!({
+        "type": "object",
+        "required": ["name", "database", "region"],
+        "additionalProperties": false,
+        "properties": {
+            "name": { "type": "string", "minLength": 1, "pattern": "^[a-z]+$" },
+            "replicas": { "type": "integer", "exclusiveMinimum": 0 },
+            "debug": { "type": "boolean" },
+            "mode": { "enum": ["slow", "normal", 1] },
+            "region": { "const": "eu" },
+            "database": { "$ref": "#/$defs/Database" },
+            "tags": {
+                "type": "array",
+                "maxItems": 2,
+                "items": { "type": "string", "maxLength": 2 },
+            },
+        },
+        "$defs": {
+            "Database": {
+                "type": "object",
+                "required": ["host", "user"],
+                "properties": {
+                    "host": { "type": "string" },
+                    "port": { "type": "integer", "minimum": 1, "maximum": 65535 },
+                    "ports": { "type": "array", "items": { "type": "integer" } },
+                },
+            },
+        },
+    }))
+    .unwrap()
+}
+
+fn violations(schema: &Schema, text: &str) -> Vec<String> {
+    let document = Document::parse(text).unwrap();
+
+    schema
+        .validate(&document)
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test]
+fn test_validate() {
+    assert_eq!(
+        violations(&schema(), CCL),
+        [
+            "missing required key `region` at line 1 column 1",
+            "replicas: must be greater than 0 at line 3 column 12",
+            "debug: expected boolean, found `yes` at line 4 column 9",
+            "mode: expected one of `slow`, `normal`, `1` at line 5 column 8",
+            "database: missing required key `user` at line 7 column 3",
+            "database.port: must be at most 65535 at line 8 column 10",
+            "database.ports[1]: expected integer, found `x` at line 11 column 7",
+            "tags: must have at most 2 items at line 13 column 3",
+            "tags[2]: must be at most 2 characters long at line 15 column 5",
+            "extra: unknown key `extra` at line 16 column 1",
+        ],
+    );
+}
+
+#[test]
+fn test_validate_valid() {
+    let text = "\
+name = app
+replicas = 3
+debug = false
+mode = 1.0
+region = eu
+database =
+  host = db.local
+  user = admin
+  ports =
+    /= Primary
+    = 5432
+tags =
+";
+
+    assert!(violations(&schema(), text).is_empty());
+}
+
+#[test]
+fn test_validate_types() {
+    let schema = Schema::new(json!({
+        "properties": {
+            "a": { "type": ["integer", "null"] },
+            "b": { "type": "object" },
+            "c": { "type": "array" },
+            "d": { "type": "number" },
+        },
+    }))
+    .unwrap();
+
+    assert!(violations(&schema, "a =\nb =\nc =\nd = 1.5\n").is_empty());
+    assert!(violations(&schema, "a = 1\nb =\n  x = 1\nc =\n  = 1\nd = -2\n").is_empty());
+
+    assert_eq!(
+        violations(&schema, "a = x\nb =\n  = 1\nc =\n  x = 1\nd = x\n"),
+        [
+            "a: expected integer or null, found `x` at line 1 column 5",
+            "b: expected object, found a list at line 3 column 3",
+            "c: expected array, found a map at line 5 column 3",
+            "d: expected number, found `x` at line 6 column 5",
+        ],
+    );
+}
+
+#[test]
+fn test_validate_combinators() {
+    let schema = Schema::new(json!({
+        "properties": {
+            "port": {
+                "anyOf": [
+                    { "type": "integer", "minimum": 1024 },
+                    { "enum": ["auto"] },
+                ],
+            },
+            "level": {
+                "oneOf": [
+                    { "type": "integer" },
+                    { "type": "number" },
+                ],
+            },
+            "name": {
+                "allOf": [
+                    { "minLength": 2 },
+                    { "pattern": "^[a-z]" },
+                ],
+            },
+        },
+    }))
+    .unwrap();
+
+    assert!(violations(&schema, "port = auto\nlevel = 1.5\nname = ab\n").is_empty());
+
+    assert_eq!(
+        violations(&schema, "port = 80\nlevel = 1\nname = A\n"),
+        [
+            "port: does not match any of the allowed schemas at line 1 column 8",
+            "level: does not match exactly one of the allowed schemas at line 2 column 9",
+            "name: must be at least 2 characters long at line 3 column 8",
+            "name: does not match the pattern `^[a-z]` at line 3 column 8",
+        ],
+    );
+}
+
+#[test]
+fn test_validate_keyword_properties() {
+    let schema = Schema::new(json!({
+        "properties": {
+            "default": { "type": "string", "pattern": "^[a-z]+$" },
+            "enum": { "pattern": "^[0-9]+$" },
+        },
+    }))
+    .unwrap();
+
+    assert!(violations(
+        &schema,
+        "default = abc
+enum = 12
+"
+    )
+    .is_empty());
+    assert_eq!(
+        violations(
+            &schema,
+            "default = ABC
+"
+        ),
+        ["default: does not match the pattern `^[a-z]+$` at line 1 column 11"],
+    );
+
+    let schema = Schema::new(json!({
+        "properties": {
+            "a": { "$ref": "#/examples/0" },
+        },
+        "examples": [{ "pattern": "^[a-z]+$" }],
+    }))
+    .unwrap();
+
+    assert_eq!(
+        violations(
+            &schema, "a = abc
+"
+        ),
+        ["a: cannot check the pattern `^[a-z]+$` at line 1 column 5"],
+    );
+}
+
+#[test]
+fn test_validate_reference_cycle() {
+    let schema = Schema::new(json!({
+        "properties": {
+            "a": { "$ref": "#" },
+            "b": { "$ref": "#/properties/b" },
+            "c": { "anyOf": [{ "$ref": "#/properties/c" }] },
+        },
+    }))
+    .unwrap();
+
+    assert!(violations(
+        &schema,
+        "a =
+  a =
+    a = 1
+"
+    )
+    .is_empty());
+    assert_eq!(
+        violations(
+            &schema,
+            "b = 1
+c = 1
+"
+        ),
+        [
+            "b: reference cycle at `#/properties/b` at line 1 column 5",
+            "c: does not match any of the allowed schemas at line 2 column 5",
+        ],
+    );
+}
+
+#[test]
+fn test_invalid_schema() {
+    let error = Schema::new(json!({ "pattern": "(" })).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Argument);
+    assert!(error
+        .to_string()
+        .starts_with("invalid schema: invalid pattern `(`"));
+
+    let error = Schema::new(json!({ "$ref": "#/$defs/Missing" })).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid schema: unresolved reference `#/$defs/Missing`",
+    );
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_validate_cli() {
+    use std::fs;
+    use std::process::Command;
+
+    let dir = tempfile::tempdir().unwrap();
+    let schema = dir.path().join("schema.json");
+    let file = dir.path().join("app.ccl");
+
+    fs::write(
+        &schema,
+        r#"{ "properties": { "port": { "type": "integer" } }, "required": ["name"] }"#,
+    )
+    .unwrap();
+
+    let validate = |text: &str| {
+        fs::write(&file, text).unwrap();
+
+        Command::new(env!("CARGO_BIN_EXE_ccl"))
+            .args(["validate", "--schema"])
+            .arg(&schema)
+            .arg(&file)
+            .output()
+            .unwrap()
+    };
+
+    let output = validate("name = app\nport = 80\n");
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let output = validate("port = http\n");
+    assert!(!output.status.success());
+
+    let name = file.display();
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!(
+            "{name}:1:1: missing required key `name`\n\
+             {name}:1:8: port: expected integer, found `http`\n"
+        ),
+    );
+}
@@ -0,0 +1,155 @@
+#![cfg(feature = "schemars")]
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_ccl::{Document, Schema};
+use std::collections::BTreeMap;
+
+/// Deployment settings.
+#[derive(Deserialize, JsonSchema)]
+#[allow(dead_code)]
+struct Config {
+    /// Port to listen on.
+    port: u16,
+    hosts: Vec<String>,
+    mode: Mode,
+    level: Option<Level>,
+    limits: BTreeMap<String, u32>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+enum Mode {
+    Fast,
+    Slow { delay: u32 },
+    Custom(String),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+enum Level {
+    Low,
+    High,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+struct Logging {
+    level: Level,
+    mode: Mode,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[allow(dead_code)]
+struct Single {
+    name: String,
+}
+
+const CCL: &str = "\
+port = 8080
+hosts =
+  = a.com
+  = b.com
+mode =
+  Slow =
+    delay = 10
+level =
+limits =
+  cpu = 1500
+";
+
+#[test]
+fn test_schema_annotations() {
+    let schema = Schema::for_type::<Config>().unwrap();
+    let json = schema.as_json();
+
+    assert_eq!(json["description"], "Deployment settings.");
+    assert_eq!(json.get("x-ccl"), None);
+
+    let hosts = &json["properties"]["hosts"];
+    assert_eq!(hosts["x-ccl"], "list");
+    assert_eq!(
+        hosts["description"],
+        "Written as list items, which are entries with empty keys, such as `= value`.",
+    );
+
+    let port = &json["properties"]["port"];
+    assert_eq!(port["description"], "Port to listen on.");
+    assert_eq!(port.get("x-ccl"), None);
+
+    let variants = json["$defs"]["Mode"]["oneOf"].as_array().unwrap();
+    assert_eq!(variants[0]["x-ccl"], "variant");
+    assert_eq!(
+        variants[0]["description"],
+        "Written as the `Fast` variant, an entry with the key `Fast`.",
+    );
+    assert_eq!(variants[1]["x-ccl"], "variant");
+    assert_eq!(
+        variants[1]["description"],
+        "Written as the `Slow` variant, an entry with the key `Slow`.",
+    );
+    assert_eq!(variants[2]["x-ccl"], "variant");
+
+    let levels = json["$defs"]["Level"]["oneOf"].as_array().unwrap();
+    assert_eq!(levels[0]["x-ccl"], "variant");
+    assert_eq!(levels[0]["required"][0], "Low");
+
+    let level = json["properties"]["level"]["anyOf"].as_array().unwrap();
+    assert_eq!(level[1]["x-ccl"], "empty");
+
+    let single = Schema::for_type::<Single>().unwrap();
+    assert_eq!(single.as_json().get("x-ccl"), None);
+}
+
+#[test]
+fn test_schema_validation() {
+    let schema = Schema::for_type::<Config>().unwrap();
+    let document = Document::parse(CCL).unwrap();
+
+    assert!(schema.validate(&document).is_empty());
+    serde_ccl::from_str::<Config>(CCL).unwrap();
+
+    let text = "\
+port = 70000
+hosts = a.com
+mode = Medium
+level = Highest
+limits =
+  cpu = lots
+";
+
+    let document = Document::parse(text).unwrap();
+    let violations = schema
+        .validate(&document)
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        violations,
+        [
+            "port: must be at most 65535 at line 1 column 8",
+            "hosts: expected array, found `a.com` at line 2 column 9",
+            "mode: does not match exactly one of the allowed schemas at line 3 column 8",
+            "level: does not match any of the allowed schemas at line 4 column 9",
+            "limits.cpu: expected integer, found `lots` at line 6 column 9",
+        ],
+    );
+}
+
+#[test]
+fn test_schema_unit_variants() {
+    let schema = Schema::for_type::<Logging>().unwrap();
+    let logging = Logging {
+        level: Level::High,
+        mode: Mode::Fast,
+    };
+
+    let text = serde_ccl::to_string(&logging).unwrap();
+    assert_eq!(text, "level =\n    High =\nmode =\n    Fast =\n");
+
+    let document = Document::parse(&text).unwrap();
+    assert!(schema.validate(&document).is_empty());
+    assert_eq!(serde_ccl::from_str::<Logging>(&text).unwrap(), logging);
+
+    let document = Document::parse("level = High\nmode = Fast\n").unwrap();
+    assert_eq!(schema.validate(&document).len(), 2);
+}
@@ -0,0 +1,126 @@
+#![cfg(feature = "schemars")]
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Deployment settings.
+///
+/// Generated from the `Config` type.
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+struct Config {
+    /// Name of the application.
+    name: String,
+    database: Database,
+    /// Hosts to deploy to.
+    hosts: Vec<Host>,
+    /// How requests are scheduled.
+    mode: Mode,
+    /// Log level, if any.
+    level: Option<String>,
+    /// Limits by resource.
+    limits: BTreeMap<String, u32>,
+}
+
+/// Database connection.
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+struct Database {
+    /// Port of the database.
+    port: u16,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+struct Host {
+    /// Address of the host.
+    address: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+enum Mode {
+    Fast,
+    Slow {
+        /// Delay between requests in milliseconds.
+        delay: u32,
+    },
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            name: "app".into(),
+            database: Database { port: 5432 },
+            hosts: vec![Host {
+                address: "a.com".into(),
+            }],
+            mode: Mode::Slow { delay: 10 },
+            level: None,
+            limits: BTreeMap::from([("cpu".into(), 1500)]),
+        }
+    }
+}
+
+const EXAMPLE: &str = "\
+/= Deployment settings.
+/=
+/= Generated from the `Config` type.
+
+/= Name of the application.
+name = app
+
+/= Database connection.
+database =
+    /= Port of the database.
+    port = 5432
+
+/= Hosts to deploy to.
+hosts =
+    =
+        /= Address of the host.
+        address = a.com
+
+/= How requests are scheduled.
+mode =
+    Slow =
+        /= Delay between requests in milliseconds.
+        delay = 10
+
+/= Log level, if any.
+level =
+
+/= Limits by resource.
+limits =
+    cpu = 1500
+";
+
+#[test]
+fn test_example() {
+    assert_eq!(serde_ccl::example::<Config>().unwrap(), EXAMPLE);
+}
+
+#[test]
+fn test_example_round_trip() {
+    let config = serde_ccl::from_str::<Config>(EXAMPLE).unwrap();
+    assert_eq!(config, Config::default());
+}
+
+#[test]
+fn test_example_map_round_trip() {
+    let databases = BTreeMap::from([("main".to_owned(), Database { port: 5432 })]);
+    let example = serde_ccl::example_of(&databases).unwrap();
+    assert_eq!(
+        example,
+        "main =\n    /= Port of the database.\n    port = 5432\n",
+    );
+
+    let parsed = serde_ccl::from_str::<BTreeMap<String, Database>>(&example).unwrap();
+    assert_eq!(parsed, databases);
+}
+
+#[test]
+fn test_example_of() {
+    let example = serde_ccl::example_of(&Database { port: 80 }).unwrap();
+    assert_eq!(
+        example,
+        "/= Database connection.\n\n/= Port of the database.\nport = 80\n",
+    );
+}
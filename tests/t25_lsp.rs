@@ -0,0 +1,344 @@
+#![cfg(feature = "lsp")]
+
+use lsp_server::{Message, Notification, Request, RequestId};
+use serde_json::{json, Value};
+use std::io::BufReader;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+/// Language client that talks to the server over stdio.
+struct Client {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: i32,
+    notifications: Vec<Notification>,
+}
+
+impl Client {
+    fn start() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_ccl-lsp"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let mut client = Self {
+            stdin: child.stdin.take().unwrap(),
+            stdout: BufReader::new(child.stdout.take().unwrap()),
+            child,
+            next_id: 0,
+            notifications: Vec::new(),
+        };
+
+        let result = client.request("initialize", json!({ "capabilities": {} }));
+        assert_eq!(result["capabilities"]["documentSymbolProvider"], true);
+        client.notify("initialized", json!({}));
+        client
+    }
+
+    fn send(&mut self, message: &Message) {
+        message.write(&mut self.stdin).unwrap();
+    }
+
+    fn receive(&mut self) -> Message {
+        Message::read(&mut self.stdout).unwrap().unwrap()
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        self.next_id += 1;
+        let id = RequestId::from(self.next_id);
+        self.send(&Message::Request(Request::new(
+            id.clone(),
+            method.into(),
+            params,
+        )));
+
+        loop {
+            match self.receive() {
+                Message::Response(response) if response.id == id => {
+                    assert!(response.error.is_none(), "{:?}", response.error);
+                    return response.result.unwrap_or_default();
+                }
+                Message::Notification(notification) => self.notifications.push(notification),
+                message => panic!("unexpected message {message:?}"),
+            }
+        }
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(&Message::Notification(Notification::new(
+            method.into(),
+            params,
+        )));
+    }
+
+    /// Returns the next diagnostics published by the server.
+    fn diagnostics(&mut self) -> Value {
+        loop {
+            let notification = if self.notifications.is_empty() {
+                match self.receive() {
+                    Message::Notification(notification) => notification,
+                    message => panic!("unexpected message {message:?}"),
+                }
+            } else {
+                self.notifications.remove(0)
+            };
+
+            if notification.method == "textDocument/publishDiagnostics" {
+                return notification.params;
+            }
+        }
+    }
+
+    fn open(&mut self, uri: &str, text: &str) {
+        self.notify(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": { "uri": uri, "languageId": "ccl", "version": 1, "text": text },
+            }),
+        );
+    }
+
+    fn shutdown(mut self) {
+        self.request("shutdown", Value::Null);
+        self.notify("exit", Value::Null);
+        assert!(self.child.wait().unwrap().success());
+    }
+}
+
+const URI: &str = "file:///app.ccl";
+
+#[test]
+fn test_lsp_diagnostics() {
+    let mut client = Client::start();
+
+    client.open(URI, "port = 80\nport = 8080 \n");
+    assert_eq!(
+        client.diagnostics(),
+        json!({
+            "uri": URI,
+            "diagnostics": [
+                {
+                    "range": {
+                        "start": { "line": 1, "character": 0 },
+                        "end": { "line": 1, "character": 4 },
+                    },
+                    "severity": 2,
+                    "code": "duplicate-key",
+                    "source": "ccl",
+                    "message": "duplicate key `port`, first defined on line 1",
+                },
+                {
+                    "range": {
+                        "start": { "line": 1, "character": 11 },
+                        "end": { "line": 1, "character": 12 },
+                    },
+                    "severity": 2,
+                    "code": "trailing-whitespace",
+                    "source": "ccl",
+                    "message": "trailing whitespace",
+                },
+            ],
+        }),
+    );
+
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [{ "text": "port = 80\nhost\n" }],
+        }),
+    );
+    assert_eq!(
+        client.diagnostics(),
+        json!({
+            "uri": URI,
+            "diagnostics": [{
+                "range": {
+                    "start": { "line": 1, "character": 0 },
+                    "end": { "line": 1, "character": 4 },
+                },
+                "severity": 2,
+                "code": "missing-eq",
+                "source": "ccl",
+                "message": "missing equal sign",
+            }],
+        }),
+    );
+
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 3 },
+            "contentChanges": [{ "text": "a =\n  b = 1\n  c\nz = 3\n" }],
+        }),
+    );
+    assert_eq!(
+        client.diagnostics(),
+        json!({
+            "uri": URI,
+            "diagnostics": [{
+                "range": {
+                    "start": { "line": 2, "character": 2 },
+                    "end": { "line": 2, "character": 3 },
+                },
+                "severity": 2,
+                "code": "missing-eq",
+                "source": "ccl",
+                "message": "missing equal sign",
+            }],
+        }),
+    );
+
+    client.notify(
+        "textDocument/didClose",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    assert_eq!(
+        client.diagnostics(),
+        json!({ "uri": URI, "diagnostics": [] })
+    );
+
+    client.shutdown();
+}
+
+#[test]
+fn test_lsp_symbols() {
+    let mut client = Client::start();
+    client.open(
+        URI,
+        "/= Server\nserver =\n  port = 80\n  hosts =\n    = a.com\n    = b.com\n",
+    );
+
+    let symbols = client.request(
+        "textDocument/documentSymbol",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+
+    let summary = |symbol: &Value| {
+        (
+            symbol["name"].as_str().unwrap().to_owned(),
+            symbol["kind"].as_u64().unwrap(),
+            symbol["range"]["start"]["line"].as_u64().unwrap(),
+            symbol["range"]["end"]["line"].as_u64().unwrap(),
+        )
+    };
+
+    let [server] = symbols.as_array().unwrap().as_slice() else {
+        panic!("expected a single symbol, found {symbols}");
+    };
+    assert_eq!(summary(server), ("server".into(), 19, 1, 5));
+    assert_eq!(server["selectionRange"]["end"]["character"], 6);
+
+    let children = server["children"].as_array().unwrap();
+    assert_eq!(
+        children.iter().map(summary).collect::<Vec<_>>(),
+        [("port".into(), 15, 2, 2), ("hosts".into(), 18, 3, 5)],
+    );
+    assert_eq!(children[0]["detail"], "80");
+
+    let items = children[1]["children"].as_array().unwrap();
+    assert_eq!(
+        items.iter().map(summary).collect::<Vec<_>>(),
+        [("[0]".into(), 15, 4, 4), ("[1]".into(), 15, 5, 5)],
+    );
+
+    let ranges = client.request(
+        "textDocument/foldingRange",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    assert_eq!(
+        ranges,
+        json!([
+            { "startLine": 1, "endLine": 5 },
+            { "startLine": 3, "endLine": 5 },
+        ]),
+    );
+
+    client.shutdown();
+}
+
+#[test]
+fn test_lsp_formatting() {
+    let mut client = Client::start();
+    client.open(URI, "a=1\nb =\n    c=2\n");
+
+    let params = json!({
+        "textDocument": { "uri": URI },
+        "options": { "tabSize": 2, "insertSpaces": true },
+    });
+
+    assert_eq!(
+        client.request("textDocument/formatting", params.clone()),
+        json!([{
+            "range": {
+                "start": { "line": 0, "character": 0 },
+                "end": { "line": 3, "character": 0 },
+            },
+            "newText": serde_ccl::format("a=1\nb =\n    c=2\n").unwrap(),
+        }]),
+    );
+
+    client.open(URI, "a = 1\n");
+    assert_eq!(client.request("textDocument/formatting", params), json!([]));
+
+    client.shutdown();
+}
+
+#[test]
+fn test_lsp_definition() {
+    use std::fs;
+
+    let dir = tempfile::tempdir().unwrap();
+    let dir = dir.path().canonicalize().unwrap();
+    fs::create_dir(dir.join("conf.d")).unwrap();
+    fs::write(dir.join("base.ccl"), "port = 80\n").unwrap();
+    fs::write(dir.join("conf.d/a.ccl"), "a = 1\n").unwrap();
+    fs::write(dir.join("conf.d/b.ccl"), "b = 2\n").unwrap();
+
+    let file = dir.join("app.ccl");
+    let uri = lsp_types::Url::from_file_path(&file).unwrap();
+    let uri_of = |path: &str| {
+        lsp_types::Url::from_file_path(dir.join(path))
+            .unwrap()
+            .to_string()
+    };
+
+    let text = "imports =\n  = base.ccl\n  = conf.d/*.ccl\nport = 8080\n";
+    fs::write(&file, text).unwrap();
+
+    let mut client = Client::start();
+    client.open(uri.as_str(), text);
+
+    let mut definition = |line: u32, character: u32| {
+        client.request(
+            "textDocument/definition",
+            json!({
+                "textDocument": { "uri": uri },
+                "position": { "line": line, "character": character },
+            }),
+        )
+    };
+
+    let location = |uri: String| {
+        json!({
+            "uri": uri,
+            "range": {
+                "start": { "line": 0, "character": 0 },
+                "end": { "line": 0, "character": 0 },
+            },
+        })
+    };
+
+    assert_eq!(definition(1, 6), json!([location(uri_of("base.ccl"))]));
+    assert_eq!(
+        definition(2, 4),
+        json!([
+            location(uri_of("conf.d/a.ccl")),
+            location(uri_of("conf.d/b.ccl"))
+        ]),
+    );
+    assert_eq!(definition(3, 8), Value::Null);
+
+    client.shutdown();
+}
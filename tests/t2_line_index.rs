@@ -0,0 +1,47 @@
+use serde_ccl::{ColumnMode, LineIndex, Position};
+
+const CCL: &str = "name = Ștefan\n\nemoji = 😀 = x\nlast";
+
+#[test]
+fn test_line_index_lines() {
+    let index = LineIndex::new(CCL);
+
+    assert_eq!(index.line_count(), 4);
+    assert_eq!(index.line_start(1), Some(0));
+    assert_eq!(index.line_start(2), Some(15));
+    assert_eq!(index.line_start(5), None);
+    assert_eq!(index.line_data(3), Some("emoji = 😀 = x".as_bytes()));
+
+    assert_eq!(index.line(0), 1);
+    assert_eq!(index.line(14), 1);
+    assert_eq!(index.line(15), 2);
+    assert_eq!(index.line(CCL.len()), 4);
+    assert_eq!(index.line(usize::MAX), 4);
+}
+
+#[test]
+fn test_line_index_columns() {
+    let index = LineIndex::new(CCL);
+    let eq = CCL.rfind('=').unwrap();
+
+    assert_eq!(index.position(eq, ColumnMode::Byte), Position::new(3, 14));
+    assert_eq!(index.position(eq, ColumnMode::Char), Position::new(3, 11));
+    assert_eq!(index.position(eq, ColumnMode::Utf16), Position::new(3, 12));
+
+    let t = CCL.find('t').unwrap();
+    assert_eq!(index.position(t, ColumnMode::Byte), Position::new(1, 10));
+    assert_eq!(index.position(t, ColumnMode::Char), Position::new(1, 9));
+    assert_eq!(index.position(t, ColumnMode::Utf16), Position::new(1, 9));
+}
+
+#[test]
+fn test_line_index_round_trip() {
+    let index = LineIndex::new(CCL);
+
+    for (offset, _) in CCL.char_indices() {
+        for mode in [ColumnMode::Byte, ColumnMode::Char, ColumnMode::Utf16] {
+            let position = index.position(offset, mode);
+            assert_eq!(index.index(position, mode), Some(offset));
+        }
+    }
+}
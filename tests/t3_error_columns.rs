@@ -0,0 +1,34 @@
+use serde::Deserialize;
+use serde_ccl::{ColumnMode, Position};
+use std::collections::BTreeMap;
+
+const CCL: &str = r"
+oraș = Timișoara
+端口 = 😀 x
+";
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Config {
+    oraș: String,
+    端口: u16,
+}
+
+#[test]
+fn test_error_columns() {
+    let error = serde_ccl::from_str::<Config>(CCL).unwrap_err();
+
+    assert_eq!(error.line(), 3);
+    assert_eq!(error.column(), 10);
+    assert_eq!(error.column_chars(), 6);
+    assert_eq!(error.column_utf16(), 6);
+    assert_eq!(error.position(ColumnMode::Utf16), Position::new(3, 6));
+    assert_eq!(&CCL[error.index()..], "😀 x\n");
+
+    let error = serde_ccl::from_str::<BTreeMap<String, u16>>("a = 1\n😀😀 = x").unwrap_err();
+
+    assert_eq!(error.line(), 2);
+    assert_eq!(error.column(), 12);
+    assert_eq!(error.column_chars(), 6);
+    assert_eq!(error.column_utf16(), 8);
+}
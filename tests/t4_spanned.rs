@@ -0,0 +1,100 @@
+use serde::Deserialize;
+use serde_ccl::Spanned;
+use std::collections::BTreeMap;
+
+const CCL: &str = r"
+name = Ștefan
+port = 8080
+empty =
+database =
+    host = localhost
+    ports =
+        = 5432
+        = 5433
+labels =
+    a = 1
+";
+
+#[derive(Debug, Deserialize)]
+struct Config<'a> {
+    #[serde(borrow)]
+    name: Spanned<&'a str>,
+    port: Spanned<u16>,
+    empty: Spanned<Option<u32>>,
+    #[serde(borrow)]
+    database: Spanned<Database<'a>>,
+    labels: BTreeMap<Spanned<String>, Spanned<u32>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Database<'a> {
+    #[allow(dead_code)]
+    host: &'a str,
+    ports: Vec<Spanned<u16>>,
+}
+
+#[test]
+fn test_spanned_values() {
+    let config = serde_ccl::from_str::<Config>(CCL).unwrap();
+
+    assert_eq!(*config.name, "Ștefan");
+    assert_eq!(&CCL[config.name.key_span().range()], "name");
+    assert_eq!(&CCL[config.name.value_span().range()], "Ștefan");
+    assert_eq!(config.name.key_span().line(), 2);
+    assert_eq!(config.name.key_span().column(), 1);
+    assert_eq!(config.name.value_span().column(), 8);
+
+    assert_eq!(*config.port, 8080);
+    assert_eq!(&CCL[config.port.value_span().range()], "8080");
+    assert_eq!(config.port.value_span().line(), 3);
+
+    assert!(config.empty.is_none());
+    assert_eq!(&CCL[config.empty.key_span().range()], "empty");
+    assert!(config.empty.value_span().range().is_empty());
+    assert_eq!(config.empty.value_span().line(), 4);
+
+    let database_span = config.database.value_span();
+    assert_eq!(database_span.line(), 6);
+    assert_eq!(database_span.column(), 5);
+    assert!(CCL[database_span.range()].starts_with("host = localhost"));
+    assert!(CCL[database_span.range()].ends_with("= 5433"));
+
+    let ports = &config.database.ports;
+    assert_eq!(*ports[1], 5433);
+    assert_eq!(&CCL[ports[1].value_span().range()], "5433");
+    assert_eq!(ports[1].value_span().line(), 9);
+    assert_eq!(ports[1].key_span().range().len(), 0);
+}
+
+#[test]
+fn test_spanned_keys() {
+    let config = serde_ccl::from_str::<Config>(CCL).unwrap();
+    let (key, value) = config.labels.iter().next().unwrap();
+
+    assert_eq!(key.as_str(), "a");
+    assert_eq!(&CCL[key.key_span().range()], "a");
+    assert_eq!(key.key_span(), key.value_span());
+    assert_eq!(key.key_span().line(), 11);
+    assert_eq!(**value, 1);
+    assert_eq!(&CCL[value.value_span().range()], "1");
+}
+
+#[test]
+fn test_spanned_top_level() {
+    let document =
+        serde_ccl::from_str::<Spanned<BTreeMap<&str, &str>>>("\n a = 1\n b = 2\n").unwrap();
+
+    assert_eq!(document.len(), 2);
+    assert_eq!(document.key_span().range(), 0..0);
+    assert_eq!(document.value_span().range(), 2..14);
+    assert_eq!(document.value_span().line(), 2);
+    assert_eq!(document.value_span().column(), 2);
+}
+
+#[test]
+fn test_spanned_error_position() {
+    let error = serde_ccl::from_str::<Config>("name = a\nport = x\n").unwrap_err();
+
+    assert_eq!(error.line(), 2);
+    assert_eq!(error.column(), 8);
+}
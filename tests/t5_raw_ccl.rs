@@ -0,0 +1,76 @@
+use serde::Deserialize;
+use serde_ccl::RawCcl;
+
+const CCL: &str = r"
+plugins =
+    =
+        kind = http
+        options =
+            port = 8080
+            hosts =
+                = a.example
+                = b.example
+    =
+        kind = file
+        options = /var/log/app.log
+";
+
+#[derive(Deserialize)]
+struct Config<'a> {
+    #[serde(borrow)]
+    plugins: Vec<Plugin<'a>>,
+}
+
+#[derive(Deserialize)]
+struct Plugin<'a> {
+    kind: &'a str,
+    options: &'a RawCcl,
+}
+
+#[derive(Deserialize)]
+struct HttpOptions {
+    port: u16,
+    hosts: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct OwnedPlugin {
+    options: Box<RawCcl>,
+}
+
+#[test]
+fn test_raw_ccl_deferred() {
+    let config = serde_ccl::from_str::<Config>(CCL).unwrap();
+    let [http, file] = config.plugins.as_slice() else {
+        panic!("expected two plugins");
+    };
+
+    assert_eq!(http.kind, "http");
+    assert!(http.options.get().starts_with("            port = 8080\n"));
+
+    let options = http.options.parse::<HttpOptions>().unwrap();
+    assert_eq!(options.port, 8080);
+    assert_eq!(options.hosts, ["a.example", "b.example"]);
+
+    assert_eq!(file.kind, "file");
+    assert_eq!(file.options.get(), "/var/log/app.log");
+    assert_eq!(file.options.parse::<String>().unwrap(), "/var/log/app.log");
+}
+
+#[test]
+fn test_raw_ccl_owned() {
+    let plugin = serde_ccl::from_str::<OwnedPlugin>("options =\n  port = 1\n").unwrap();
+    assert_eq!(plugin.options.get(), "  port = 1");
+
+    let options = plugin.options.clone();
+    assert_eq!(options.get(), plugin.options.get());
+}
+
+#[test]
+fn test_raw_ccl_top_level() {
+    let raw = serde_ccl::from_str::<&RawCcl>("\n  a = 1\n  b = 2\n").unwrap();
+    assert_eq!(raw.get(), "  a = 1\n  b = 2");
+
+    let port = serde_ccl::from_str::<u16>(" 8080\n").unwrap();
+    assert_eq!(port, 8080);
+}
@@ -0,0 +1,174 @@
+use serde::Deserialize;
+use serde_ccl::{Document, Entry, Source, Value};
+use std::collections::BTreeMap;
+
+const CCL: &str = r"
+/= Application settings
+name = app
+database =
+  host = localhost
+  ports =
+    = 5432
+    = 5433
+description =
+  A description
+    spanning lines
+empty =
+";
+
+#[derive(Debug, Deserialize)]
+struct Config {
+    name: String,
+    database: Database,
+    description: String,
+    empty: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Database {
+    host: String,
+    ports: Vec<u16>,
+}
+
+#[test]
+fn test_document_parse() {
+    let document = Document::parse(CCL).unwrap();
+    let keys = document
+        .entries()
+        .iter()
+        .map(Entry::key)
+        .collect::<Vec<_>>();
+    assert_eq!(keys, ["/", "name", "database", "description", "empty"]);
+
+    let database = document.get("database").unwrap();
+    assert_eq!(database.origin().key_span().line(), 4);
+
+    let database = database.value().as_block().unwrap();
+    assert_eq!(
+        database.get("host").unwrap().value(),
+        &Value::from("localhost")
+    );
+
+    let ports = database.get("ports").unwrap().value().as_block().unwrap();
+    let port = &ports.entries()[1];
+    assert_eq!(port.key(), "");
+    assert_eq!(port.value(), &Value::from("5433"));
+    assert_eq!(port.origin().value_span().line(), 8);
+    assert_eq!(port.origin().value_span().column(), 7);
+
+    let description = document.get("description").unwrap().value();
+    assert_eq!(
+        description.as_text(),
+        Some("A description\n    spanning lines")
+    );
+    assert!(document.get("empty").unwrap().value().is_empty());
+}
+
+#[test]
+fn test_document_round_trip() {
+    let document = Document::parse(CCL).unwrap();
+    let rendered = document.to_string();
+
+    assert_eq!(Document::parse(&rendered).unwrap(), document);
+
+    let config = serde_ccl::from_document::<Config>(&document).unwrap();
+    assert_eq!(config.name, "app");
+    assert_eq!(config.database.host, "localhost");
+    assert_eq!(config.database.ports, [5432, 5433]);
+    assert_eq!(config.description, "A description\n    spanning lines");
+    assert!(config.empty.is_none());
+}
+
+#[test]
+fn test_document_nested_text() {
+    let mut document = Document::new();
+    let mut block = Document::new();
+    block.push(Entry::new("text", "first\nsecond"));
+    document.push(Entry::new("block", block));
+
+    let rendered = document.to_string();
+    assert_eq!(rendered, "block =\n    text = first\n        second\n");
+
+    let map = serde_ccl::from_document::<BTreeMap<String, BTreeMap<String, String>>>(&document);
+    assert_eq!(map.unwrap()["block"]["text"], "first\n        second");
+}
+
+#[test]
+fn test_document_error_location() {
+    let text = "name = app\ndatabase =\n  host = localhost\n  ports =\n    = 5432\n    = x\n";
+    let document = Document::parse_with_source(text, Source::named("app.ccl")).unwrap();
+    let error = serde_ccl::from_document::<Config>(&document).unwrap_err();
+
+    assert_eq!(error.input(), Some(&Source::named("app.ccl")));
+    assert_eq!(error.line(), 6);
+    assert_eq!(error.column(), 7);
+    assert_eq!(
+        error.to_string(),
+        "invalid int in app.ccl at line 6 column 7"
+    );
+}
+
+#[test]
+fn test_document_syntax_error() {
+    let error = Document::parse_with_source("a = 1\nb\n", Source::named("bad.ccl")).unwrap_err();
+
+    assert_eq!(error.input(), Some(&Source::named("bad.ccl")));
+    assert_eq!(error.line(), 3);
+}
+
+#[test]
+fn test_document_keeps_value_text() {
+    type Map = BTreeMap<String, String>;
+
+    let text = "\
+script =
+  echo a=b
+database =
+  host=localhost
+   port = 5432
+desc =
+  hello world
+  key = v
+next = 1
+";
+
+    let document = Document::parse(text).unwrap();
+    assert!(document.get("script").unwrap().value().as_block().is_some());
+    assert_eq!(
+        document.get("desc").unwrap().value().as_text(),
+        Some("hello world\n  key = v"),
+    );
+
+    let map = serde_ccl::from_document::<Map>(&document).unwrap();
+    assert_eq!(map, serde_ccl::from_str::<Map>(text).unwrap());
+    assert_eq!(map["script"], "echo a=b");
+
+    let reparsed = Document::parse(&document.to_string()).unwrap();
+    assert_eq!(serde_ccl::from_document::<Map>(&reparsed).unwrap(), map);
+    assert_eq!(reparsed, document);
+}
+
+#[test]
+fn test_document_modified_block() {
+    let mut document = Document::parse("database =\n  host=localhost\n").unwrap();
+    let database = document.get_mut("database").unwrap().value_mut();
+    database
+        .as_block_mut()
+        .unwrap()
+        .push(Entry::new("port", "5432"));
+
+    assert_eq!(
+        document.to_string(),
+        "database =\n    host = localhost\n    port = 5432\n",
+    );
+}
+
+#[test]
+fn test_document_multiline_key() {
+    let error = Document::parse("name = app\nhost\nport = 80\n").unwrap_err();
+    assert_eq!((error.line(), error.column()), (2, 1));
+    assert_eq!(
+        error.to_string(),
+        "expected equal sign in <anonymous> at line 2 column 1"
+    );
+}
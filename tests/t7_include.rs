@@ -0,0 +1,164 @@
+#![cfg(feature = "std")]
+
+use serde::Deserialize;
+use serde_ccl::{ErrorKind, FileResolver, Loader, MemoryResolver, Source};
+use std::collections::BTreeMap;
+use std::fs;
+
+#[derive(Debug, Deserialize)]
+struct Config {
+    theme: String,
+    font: Font,
+    dynamic_title: bool,
+    font_size: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct Font {
+    family: String,
+}
+
+fn resolver() -> MemoryResolver {
+    MemoryResolver::new()
+        .with_home("/home/user")
+        .with_document(
+            "/etc/terminal/terminal.ccl",
+            "imports =\n    = ~/.config/terminal/theme.ccl\n    = ./font.ccl\n\ndynamic_title = false\nfont_size = 12\n",
+        )
+        .with_document("/home/user/.config/terminal/theme.ccl", "theme = dark\n")
+        .with_document(
+            "/etc/terminal/font.ccl",
+            "font =\n    imports = fonts/family.ccl\n",
+        )
+        .with_document("/etc/terminal/fonts/family.ccl", "family = Iosevka\n")
+}
+
+#[test]
+fn test_include_memory() {
+    let document = Loader::new(resolver())
+        .load("/etc/terminal/terminal.ccl")
+        .unwrap();
+    let config = serde_ccl::from_document::<Config>(&document).unwrap();
+
+    assert_eq!(config.theme, "dark");
+    assert_eq!(config.font.family, "Iosevka");
+    assert!(!config.dynamic_title);
+    assert_eq!(config.font_size, 12);
+
+    let family = document.get("font").unwrap().value().as_block().unwrap();
+    let family = family.get("family").unwrap();
+    assert_eq!(
+        family.origin().source(),
+        &Source::named("/etc/terminal/fonts/family.ccl"),
+    );
+}
+
+#[test]
+fn test_include_custom_key() {
+    let resolver = MemoryResolver::new()
+        .with_document("/a.ccl", "include = b.ccl\n")
+        .with_document("/b.ccl", "b = 1\n");
+
+    let document = Loader::new(resolver)
+        .with_include_key("include")
+        .load("/a.ccl")
+        .unwrap();
+
+    assert_eq!(document.to_string(), "b = 1\n");
+}
+
+#[test]
+fn test_include_keeps_value_text() {
+    let resolver = MemoryResolver::new()
+        .with_document("/a.ccl", "imports = b.ccl\nscript =\n  echo a=b\n")
+        .with_document("/b.ccl", "setup =\n  export A=1\n");
+
+    let document = Loader::new(resolver).load("/a.ccl").unwrap();
+    let map = serde_ccl::from_document::<BTreeMap<String, String>>(&document).unwrap();
+
+    assert_eq!(map["script"], "echo a=b");
+    assert_eq!(map["setup"], "export A=1");
+}
+
+#[test]
+fn test_include_cycle() {
+    let resolver = MemoryResolver::new()
+        .with_document("/a.ccl", "imports = b.ccl\n")
+        .with_document("/b.ccl", "x = 1\nimports = a.ccl\n");
+
+    let error = Loader::new(resolver).load("/a.ccl").unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Include);
+    assert_eq!(error.input(), Some(&Source::named("/b.ccl")));
+    assert_eq!(error.line(), 2);
+    assert_eq!(error.column(), 11);
+}
+
+#[test]
+fn test_include_missing() {
+    let resolver = MemoryResolver::new().with_document("/a.ccl", "x = 1\nimports = b.ccl\n");
+
+    let error = Loader::new(resolver).load("/a.ccl").unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Io);
+    assert_eq!(error.input(), Some(&Source::named("/a.ccl")));
+    assert_eq!(error.line(), 2);
+}
+
+#[test]
+fn test_include_error_location() {
+    let resolver = MemoryResolver::new()
+        .with_document(
+            "/app.ccl",
+            "imports = theme.ccl
+font =
+  family = mono
+font_size = 1
+",
+        )
+        .with_document(
+            "/theme.ccl",
+            "/= Theme
+theme = dark
+dynamic_title = maybe
+",
+        );
+
+    let document = Loader::new(resolver).load("/app.ccl").unwrap();
+    let error = serde_ccl::from_document::<Config>(&document).unwrap_err();
+
+    assert_eq!(error.input(), Some(&Source::named("/theme.ccl")));
+    assert_eq!(error.line(), 3);
+    assert_eq!(error.column(), 17);
+}
+
+#[test]
+fn test_include_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("app.ccl");
+    fs::create_dir(dir.path().join("conf")).unwrap();
+    fs::write(&root, "imports = conf/theme.ccl\nfont_size = 12\n").unwrap();
+    fs::write(
+        dir.path().join("conf/theme.ccl"),
+        "theme = light\nfont_size = x\n",
+    )
+    .unwrap();
+
+    let loader = Loader::new(FileResolver::new());
+    let document = loader.load(root.to_str().unwrap()).unwrap();
+    let theme = document.get("theme").unwrap();
+    assert!(theme
+        .origin()
+        .source()
+        .name()
+        .unwrap()
+        .ends_with("theme.ccl"));
+
+    let error = serde_ccl::from_document::<std::collections::BTreeMap<String, u32>>(&document);
+    let error = error.unwrap_err();
+    assert!(error
+        .input()
+        .unwrap()
+        .name()
+        .unwrap()
+        .ends_with("theme.ccl"));
+    assert_eq!(error.line(), 1);
+}
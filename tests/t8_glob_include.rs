@@ -0,0 +1,98 @@
+#![cfg(feature = "std")]
+
+use serde_ccl::{ErrorKind, FileResolver, Loader, MemoryResolver, Source};
+use std::collections::BTreeMap;
+use std::fs;
+
+fn resolver() -> MemoryResolver {
+    MemoryResolver::new()
+        .with_document(
+            "/etc/app/app.ccl",
+            "port = 80\nimports = conf.d/*.ccl\nname = app\n",
+        )
+        .with_document(
+            "/etc/app/conf.d/20-port.ccl",
+            "port = 8080\nname = twenty\n",
+        )
+        .with_document(
+            "/etc/app/conf.d/10-port.ccl",
+            "port = 443\nlog =\n  level = info\n",
+        )
+        .with_document("/etc/app/conf.d/30-log.ccl", "log =\n  file = app.log\n")
+        .with_document("/etc/app/conf.d/README", "not = included\n")
+        .with_document("/etc/app/conf.d/.hidden.ccl", "port = 1\n")
+}
+
+#[test]
+fn test_glob_include_order() {
+    let document = Loader::new(resolver()).load("/etc/app/app.ccl").unwrap();
+
+    assert_eq!(
+        document.to_string(),
+        "port = 8080\nlog =\n    level = info\n    file = app.log\nname = app\n",
+    );
+
+    let port = document.get("port").unwrap();
+    assert_eq!(
+        port.origin().source(),
+        &Source::named("/etc/app/conf.d/20-port.ccl"),
+    );
+}
+
+#[test]
+fn test_glob_include_no_match() {
+    let resolver = MemoryResolver::new().with_document("/a.ccl", "imports = d/*.ccl\nx = 1\n");
+    let document = Loader::new(resolver).load("/a.ccl").unwrap();
+    assert_eq!(document.to_string(), "x = 1\n");
+}
+
+#[test]
+fn test_glob_include_error_names_fragment() {
+    let resolver = resolver().with_document("/etc/app/conf.d/15-broken.ccl", "port\n");
+
+    let error = Loader::new(resolver).load("/etc/app/app.ccl").unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Syntax);
+    assert_eq!(
+        error.input(),
+        Some(&Source::named("/etc/app/conf.d/15-broken.ccl")),
+    );
+}
+
+#[test]
+fn test_glob_include_allowed_root() {
+    let resolver =
+        resolver().with_document("/etc/app/conf.d/40-escape.ccl", "imports = /secret.ccl\n");
+    let resolver = resolver.with_document("/secret.ccl", "password = hunter2\n");
+
+    let error = Loader::new(resolver)
+        .with_allowed_root("/etc/app")
+        .load("/etc/app/app.ccl")
+        .unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::Include);
+    assert_eq!(
+        error.input(),
+        Some(&Source::named("/etc/app/conf.d/40-escape.ccl")),
+    );
+    assert_eq!(error.line(), 1);
+    assert_eq!(error.column(), 11);
+}
+
+#[test]
+fn test_glob_include_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("app.ccl");
+    fs::create_dir(dir.path().join("conf.d")).unwrap();
+    fs::write(&root, "imports = conf.d/*.ccl\n").unwrap();
+    fs::write(dir.path().join("conf.d/b.ccl"), "x = 2\n").unwrap();
+    fs::write(dir.path().join("conf.d/a.ccl"), "x = 1\ny = 1\n").unwrap();
+    fs::write(dir.path().join("conf.d/c.txt"), "x = 3\n").unwrap();
+
+    let document = Loader::new(FileResolver::new())
+        .with_allowed_root(fs::canonicalize(dir.path()).unwrap())
+        .load(root.to_str().unwrap())
+        .unwrap();
+
+    let values = serde_ccl::from_document::<BTreeMap<String, u32>>(&document).unwrap();
+    assert_eq!(values, BTreeMap::from([("x".into(), 2), ("y".into(), 1)]));
+}
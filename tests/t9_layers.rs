@@ -0,0 +1,122 @@
+use serde::Deserialize;
+use serde_ccl::{Document, Layers, ListPolicy, MergeOptions, Source};
+
+#[derive(Debug, Deserialize)]
+struct Config {
+    name: String,
+    server: Server,
+}
+
+#[derive(Debug, Deserialize)]
+struct Server {
+    port: u16,
+    timeout: u32,
+    hosts: Vec<String>,
+}
+
+fn layers() -> Layers {
+    let layer = |name: &str, text: &str| {
+        (
+            name.to_owned(),
+            Document::parse_with_source(text, Source::named(name)).unwrap(),
+        )
+    };
+
+    [
+        layer(
+            "defaults",
+            "name = app\nserver =\n  port = 80\n  timeout = 30\n  hosts =\n    = localhost\n",
+        ),
+        layer("system", "server =\n  port = 443\n"),
+        layer("user", "server =\n  hosts =\n    = example.com\n"),
+        layer("overrides", "name = custom\n"),
+    ]
+    .into_iter()
+    .fold(Layers::new(), |layers, (name, document)| {
+        layers.with_layer(name, document)
+    })
+}
+
+#[test]
+fn test_layers_append() {
+    let config = layers().deserialize::<Config>().unwrap();
+
+    assert_eq!(config.name, "custom");
+    assert_eq!(config.server.port, 443);
+    assert_eq!(config.server.timeout, 30);
+    assert_eq!(config.server.hosts, ["localhost", "example.com"]);
+}
+
+#[test]
+fn test_layers_replace() {
+    let options = MergeOptions::new().with_list_policy(ListPolicy::Replace);
+    let layers = layers().with_options(options);
+    let config = layers.deserialize::<Config>().unwrap();
+
+    assert_eq!(config.server.port, 443);
+    assert_eq!(config.server.hosts, ["example.com"]);
+
+    let merged = layers.merge();
+    let server = merged.get("server").unwrap().value().as_block().unwrap();
+    assert_eq!(
+        server.get("port").unwrap().origin().source(),
+        &Source::named("system"),
+    );
+}
+
+#[test]
+fn test_layers_error_location() {
+    let layers = layers().with_layer(
+        "env",
+        Document::parse_with_source("server =\n  timeout = soon\n", Source::named("env.ccl"))
+            .unwrap(),
+    );
+
+    let error = layers.deserialize::<Config>().unwrap_err();
+    assert_eq!(error.input(), Some(&Source::named("env.ccl")));
+    assert_eq!(error.line(), 2);
+    assert_eq!(error.column(), 13);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_merge_cli() {
+    use std::fs;
+    use std::process::Command;
+
+    let dir = tempfile::tempdir().unwrap();
+
+    fs::write(
+        dir.path().join("base.ccl"),
+        "name = app\nserver =\n  port = 80\n  hosts =\n    = localhost\n",
+    )
+    .unwrap();
+
+    fs::write(
+        dir.path().join("override.ccl"),
+        "server =\n  port = 443\n  hosts =\n    = example.com\n",
+    )
+    .unwrap();
+
+    let merge = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_ccl"))
+            .current_dir(dir.path())
+            .arg("merge")
+            .args(args)
+            .args(["base.ccl", "override.ccl"])
+            .output()
+            .unwrap();
+
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert_eq!(
+        merge(&[]),
+        "name = app\nserver =\n    port = 443\n    hosts =\n        = localhost\n        = example.com\n",
+    );
+    assert_eq!(
+        merge(&["--lists", "replace"]),
+        "name = app\nserver =\n    port = 443\n    hosts =\n        = example.com\n",
+    );
+}